@@ -30,12 +30,18 @@ pub trait ToBigCoord {
 pub trait FromBigCoord {
     type Output;
     fn try_from(self) -> Self::Output;
+    /// Returns None, if value doesn't fit into Output
+    fn checked_from(self) -> Option<Self::Output>;
 }
 
 pub trait GetEpsilon {
     fn get_epsilon() -> Self;
 }
 
+pub trait FromInt {
+    fn from_int(input: u32) -> Self;
+}
+
 // Here comes the implementations 
 // Want to change Coord type => impl trait for it here
 impl IntDiv for I10F22 {
@@ -48,6 +54,16 @@ impl IntDiv for I10F22 {
     }
 }
 
+impl IntDiv for I42F22 {
+    fn integer_division_u16(self, rhs: I42F22) -> u16 {
+        (self / rhs).to_num::<u16>()
+    }
+
+    fn integer_division_u32(self, rhs: I42F22) -> u32 {
+        (self / rhs).to_num::<u32>()
+    }
+}
+
 impl FromRaw for I10F22 {
     fn from_raw(input: i32) -> Self {
         I10F22::from_bits(input)
@@ -99,6 +115,10 @@ impl FromBigCoord for I42F22 {
     fn try_from(self) -> Self::Output {
         I10F22::from_fixed(self)
     }
+
+    fn checked_from(self) -> Option<Self::Output> {
+        I10F22::checked_from_fixed(self)
+    }
 }
 
 impl GetEpsilon for I42F22 {
@@ -106,3 +126,16 @@ impl GetEpsilon for I42F22 {
         I42F22::from_num(0.00001f64)
     }
 }
+
+impl FromInt for I10F22 {
+    /// Saturates on values, which don't fit into 10 integer bits
+    fn from_int(input: u32) -> Self {
+        I10F22::saturating_from_num(input)
+    }
+}
+
+impl FromInt for I42F22 {
+    fn from_int(input: u32) -> Self {
+        I42F22::from_num(input)
+    }
+}
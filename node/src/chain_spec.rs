@@ -1,6 +1,6 @@
 use node_dsky_runtime::{
     AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-    SystemConfig, DSAccount, DSAccountsModuleConfig, TransactionPaymentConfig, WASM_BINARY,
+    SystemConfig, DSAccount, DSAccountsModuleConfig, DSMapsModuleConfig, TransactionPaymentConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -170,6 +170,7 @@ fn testnet_genesis(
                 .into_iter()
                 .collect(),
         }),
+        pallet_ds_maps: Some(DSMapsModuleConfig::default()),
         pallet_transaction_payment: Some(TransactionPaymentConfig {}),
     }
 }
@@ -7,7 +7,7 @@
 
 use std::sync::Arc;
 
-use node_dsky_runtime::{opaque::Block, AccountId, Balance, Index};
+use node_dsky_runtime::{opaque::Block, AccountId, Balance, Coord, Index};
 pub use sc_rpc_api::DenyUnsafe;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
@@ -32,10 +32,12 @@ where
     C: Send + Sync + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+    C::Api: pallet_ds_maps_rpc::DSMapsRuntimeApi<Block, Coord>,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
 {
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+    use pallet_ds_maps_rpc::{DSMaps, DSMapsApi};
     use substrate_frame_rpc_system::{FullSystem, SystemApi};
 
     let mut io = jsonrpc_core::IoHandler::default();
@@ -52,9 +54,11 @@ where
     )));
 
     io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(
-        client,
+        client.clone(),
     )));
 
+    io.extend_with(DSMapsApi::to_delegate(DSMaps::new(client)));
+
     // Extend this RPC with a custom API by using the following syntax.
     // `YourRpcStruct` should have a reference to a client, which is needed
     // to call into the runtime.
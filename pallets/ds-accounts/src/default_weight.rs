@@ -9,6 +9,10 @@ impl crate::WeightInfo for () {
         1_000_000_u64.saturating_add(DbWeight::get().reads_writes(1, 1))
     }
 
+    fn account_enable() -> Weight {
+        1_000_000_u64.saturating_add(DbWeight::get().reads_writes(2, 2))
+    }
+
     fn register_pilot() -> Weight {
         1_000_000_u64
             .saturating_add(DbWeight::get().writes(1_u64))
@@ -18,4 +22,13 @@ impl crate::WeightInfo for () {
         1_000_000_u64
             .saturating_add(DbWeight::get().writes(1_u64))
     }
+
+    fn grant_temporary_role() -> Weight {
+        1_000_000_u64.saturating_add(DbWeight::get().reads_writes(2, 2))
+    }
+
+    // Registry and version are read, each record is written with its role index entry
+    fn import_accounts(records: u32) -> Weight {
+        1_000_000_u64.saturating_add(DbWeight::get().reads_writes(2, 2 * records as Weight))
+    }
 }
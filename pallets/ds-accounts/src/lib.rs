@@ -6,8 +6,9 @@ use serde::{Deserialize, Serialize};
 use frame_support::{
     codec::{Decode, Encode},
     decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,
+    storage::{IterableStorageMap, IterableStorageDoubleMap},
     sp_runtime::{
-        sp_std::ops::{BitAnd, BitOr},
+        sp_std::ops::{BitAnd, BitOr, BitXor},
         traits::{
             AtLeast32Bit, Member, 
             MaybeSerializeDeserialize, Zero
@@ -15,12 +16,13 @@ use frame_support::{
     },
     traits::{
         Currency, Get, LockableCurrency,
-        OnKilledAccount,
+        OnKilledAccount, OnNewAccount,
     },
     weights::{Weight},
     Parameter,
 };
-use frame_system::ensure_signed;
+use frame_system::{ensure_signed, ensure_root};
+use frame_support::sp_std::vec::Vec;
 
 mod default_weight;
 #[cfg(test)]
@@ -37,7 +39,7 @@ pub mod prelude {
 
 /// Structure, specific for each role
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-#[derive(Encode, Decode, Clone, Default)]
+#[derive(Encode, Decode, Clone, Default, Debug, PartialEq, Eq)]
 pub struct Account<Moment, AccountRole, AccountManager> {
     pub roles: AccountRole,
     pub create_time: Moment,
@@ -100,6 +102,20 @@ impl<
     }
 
 
+/// Versions of stored account layout, used to match backups with the registry
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Releases {
+    /// Accounts with roles, creation time and manager
+    V1,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
+    }
+}
+
 pub type AccountOf<T> = Account<<T as pallet_timestamp::Config>::Moment, <T as Trait>::AccountRole, <T as frame_system::Config>::AccountId>;
 pub type UAVOf<T> = UAVStruct<<T as Trait>::SerialNumber, <T as Trait>::MetaIPFS, <T as frame_system::Config>::AccountId>;
 
@@ -118,18 +134,29 @@ pub trait Trait: frame_system::Config + pallet_timestamp::Config {
         + From<u8>
         + Copy
         + BitAnd<Output = Self::AccountRole>
-        + BitOr<Output = Self::AccountRole>;
+        + BitOr<Output = Self::AccountRole>
+        + BitXor<Output = Self::AccountRole>;
     type Currency: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
     type WeightInfo: WeightInfo;
     type SerialNumber: Default + Parameter + Clone;
     type MetaIPFS: Default + Parameter + Clone;  
+    /// If true, account reaped due to low balance gets its roles back, once it is topped up
+    type AutoReenableOnTopUp: Get<bool>;
+    /// Max amount of mutating calls, which single account can make during a day.
+    /// Shared by all pallets, which count their calls with note_operation
+    type MaxOpsPerDay: Get<u32>;
+    /// Length of a day in blocks, used for MaxOpsPerDay
+    type BlocksPerDay: Get<Self::BlockNumber>;
 }
 
 pub trait WeightInfo {
     fn account_disable() -> Weight;
+    fn account_enable() -> Weight;
     fn account_add() -> Weight;
     fn register_pilot() -> Weight;
     fn register_uav() -> Weight;
+    fn grant_temporary_role() -> Weight;
+    fn import_accounts(records: u32) -> Weight;
 }
 
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -140,6 +167,10 @@ pub const ADMIN_ROLE: u8 = 0x01;
 pub const PILOT_ROLE: u8 = 0x02;
 pub const REGISTRAR_ROLE: u8 = 0x04;
 const ALL_ROLES: u8 = ADMIN_ROLE | PILOT_ROLE | REGISTRAR_ROLE;
+/// Each role separately, used as keys of role index
+const SINGLE_ROLES: [u8; 3] = [ADMIN_ROLE, PILOT_ROLE, REGISTRAR_ROLE];
+/// Max amount of accounts, returned by a single query
+pub const MAX_ACCOUNTS_PER_QUERY: u32 = 256;
 
 // Storage, Events, Errors are declared using rust macros
 // How to use macros see
@@ -162,6 +193,48 @@ decl_storage! {
         UAVRegistry
             get(fn drone_registry):
             map hasher(blake2_128_concat) T::AccountId => UAVOf<T>;
+
+        /// Accounts, which have given role. Keys are single roles, not combinations
+        AccountsByRole
+            build(|config: &GenesisConfig<T>| {
+                SINGLE_ROLES.iter().flat_map(|role| {
+                    let role: T::AccountRole = (*role).into();
+                    config.genesis_account_registry.iter()
+                        .filter(move |(_, acc)| acc.role_is(role))
+                        .map(move |(account, _)| (role, account.clone(), ()))
+                }).collect::<Vec<_>>()
+            }):
+            double_map hasher(blake2_128_concat) T::AccountRole,
+                       hasher(blake2_128_concat) T::AccountId => ();
+
+        /// Accounts, reaped due to low balance, which are restored on top up
+        ReapedAccounts
+            get(fn reaped_account):
+            map hasher(blake2_128_concat) T::AccountId => Option<AccountOf<T>>;
+
+        /// Accounts, disabled by admin, kept to be enabled again with the same data
+        DisabledAccounts
+            get(fn disabled_account):
+            map hasher(blake2_128_concat) T::AccountId => Option<AccountOf<T>>;
+
+        /// Roles, granted temporarily, which are reverted at given block [block, account => roles]
+        TemporaryRoles
+            get(fn temporary_roles):
+            double_map hasher(blake2_128_concat) T::BlockNumber,
+                       hasher(blake2_128_concat) T::AccountId => T::AccountRole;
+
+        /// Blocks, at which temporary roles of an account are reverted. Reverse index of TemporaryRoles
+        TemporaryRoleExpiries
+            get(fn temporary_role_expiries):
+            map hasher(blake2_128_concat) T::AccountId => Vec<T::BlockNumber>;
+
+        /// Amount of mutating calls, made by account during the day [day index, amount]
+        DailyOperations
+            get(fn daily_operations):
+            map hasher(blake2_128_concat) T::AccountId => (T::BlockNumber, u32);
+
+        /// Layout of stored accounts. New chains start with the latest one
+        StorageVersion get(fn storage_version) build(|_| Releases::V1): Releases;
     }
 }
 
@@ -174,18 +247,29 @@ decl_event!(
         Balance = BalanceOf<T>,
         AccountRole = <T as Trait>::AccountRole,
         MetaIPFS = <T as Trait>::MetaIPFS,
+        BlockNumber = <T as frame_system::Config>::BlockNumber,
     {
         // Event documentation should end with an array that provides descriptive names for event parameters.
         /// New account has been created [who, account, role]
         AccountCreated(AccountId, AccountId, AccountRole),
         /// Account has been disabled [who, account]
         AccountDisabled(AccountId, AccountId),
+        /// Disabled account has been enabled again [who, account]
+        AccountEnabled(AccountId, AccountId),
         /// Lock balance [who, balance]
         BalanceLocked(AccountId, Balance),
         /// Pilot has been registered [who, account, license_ipfs_hash]
         PilotRegistered(AccountId, AccountId, MetaIPFS),
         /// UAV has been registered [who, account, license_ipfs_hash]
         UAVRegistred(AccountId, AccountId, MetaIPFS),
+        /// Role has been granted until given block [who, account, role, until]
+        TemporaryRoleGranted(AccountId, AccountId, AccountRole, BlockNumber),
+        /// Temporary role has been removed [account, role]
+        TemporaryRoleReverted(AccountId, AccountRole),
+        /// Reaped account has been topped up and restored [account]
+        AccountReenabled(AccountId),
+        /// Account registry has been restored from backup [amount of accounts]
+        AccountsImported(u32),
         // add other events here
     }
 );
@@ -210,6 +294,10 @@ decl_error! {
         AlreadyRegistered,
         /// Address doesnt belong to drone
         AddressAlreadyUsed,
+        /// Account has made max amount of operations for today
+        DailyLimitReached,
+        /// Backup was made with a layout, which is not supported by the registry
+        UnsupportedVersion,
         // add additional errors below
     }
 }
@@ -228,6 +316,37 @@ decl_module! {
         // Events must be initialized if they are used by the pallet.
         fn deposit_event() = default;
 
+        /// Checks relations between pallet constants, so misconfigured runtime fails loudly
+        fn integrity_test() {
+            assert!(T::BlocksPerDay::get() > Zero::zero(), "BlocksPerDay must be positive");
+        }
+
+        /// Reverts temporary roles, which expire at this block
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let mut reverted: Weight = 0;
+            for (account, granted) in TemporaryRoles::<T>::drain_prefix(now) {
+                reverted += 1;
+                let mut expiries = TemporaryRoleExpiries::<T>::take(&account);
+                expiries.retain(|block| *block != now);
+                if !expiries.is_empty() {
+                    TemporaryRoleExpiries::<T>::insert(&account, expiries);
+                }
+                if !AccountRegistry::<T>::contains_key(&account) {
+                    continue;
+                }
+                let roles = AccountRegistry::<T>::get(&account).roles;
+                let remaining = roles ^ (roles & granted);
+                Self::update_role_index(&account, roles, remaining);
+                if remaining.is_zero() {
+                    AccountRegistry::<T>::remove(&account);
+                } else {
+                    AccountRegistry::<T>::mutate(&account, |acc| acc.roles = remaining);
+                }
+                Self::deposit_event(RawEvent::TemporaryRoleReverted(account, granted));
+            }
+            T::DbWeight::get().reads_writes(reverted * 3, reverted * 3)
+        }
+
         /// Create or update an entry in account registry with specific role.
         #[weight = <T as Trait>::WeightInfo::account_add()]
         pub fn account_add(origin, account: T::AccountId, role: T::AccountRole) -> dispatch::DispatchResult {
@@ -239,8 +358,11 @@ decl_module! {
             ensure!(role != PILOT_ROLE.into(), Error::<T>::NotAllowedRole);
             ensure!(Self::account_is(&who, ADMIN_ROLE.into()), Error::<T>::NotAuthorized);
             ensure!(!UAVRegistry::<T>::contains_key(&account), Error::<T>::AddressAlreadyUsed);
+            Self::note_operation(&who)?;
 
-            // Update storage.
+            // Update storage. Roles, set here, are permanent, even if they were granted temporarily before
+            Self::cancel_temporary_roles(&account, role);
+            Self::update_role_index(&account, AccountRegistry::<T>::get(&account).roles, role);
             AccountRegistry::<T>::mutate(&account, |acc|{
                 acc.roles = role;
                 if acc.create_time.is_zero() {
@@ -261,7 +383,10 @@ decl_module! {
             let who = ensure_signed(origin)?;
             ensure!(Self::account_is(&who, REGISTRAR_ROLE.into()), Error::<T>::NotAuthorized);
             ensure!(!UAVRegistry::<T>::contains_key(&account), Error::<T>::AddressAlreadyUsed);
+            ensure!(!Self::account_is(&account, PILOT_ROLE.into()), Error::<T>::AlreadyRegistered);
+            Self::note_operation(&who)?;
 
+            let old_roles = AccountRegistry::<T>::get(&account).roles;
             let update_storage_result = AccountRegistry::<T>::mutate(&account, |acc| -> dispatch::DispatchResult {
                 ensure!(!AccountOf::<T>::role_is(acc, PILOT_ROLE.into()), Error::<T>::AlreadyRegistered);
 
@@ -275,6 +400,7 @@ decl_module! {
             });
 
             if update_storage_result.is_ok() {
+                Self::update_role_index(&account, old_roles, old_roles | PILOT_ROLE.into());
                 Self::deposit_event(RawEvent::PilotRegistered(who, account, metadata_ipfs_hash));
             }
 
@@ -289,6 +415,7 @@ decl_module! {
             let who = ensure_signed(origin)?;
             ensure!(Self::account_is(&who, (REGISTRAR_ROLE | PILOT_ROLE).into()), Error::<T>::NotAuthorized);
             ensure!(!AccountRegistry::<T>::contains_key(&uav_address), Error::<T>::AddressAlreadyUsed);
+            Self::note_operation(&who)?;
 
             UAVRegistry::<T>::insert(&uav_address, UAVOf::<T>::new(serial_number, meta.clone(), &who));
 
@@ -296,6 +423,59 @@ decl_module! {
             Ok(())
         }
 
+        /// Grant role to an account until given block. Roles, which account already has, are kept after revert.
+        #[weight = <T as Trait>::WeightInfo::grant_temporary_role()]
+        pub fn grant_temporary_role(origin,
+                                    account: T::AccountId,
+                                    role: T::AccountRole,
+                                    until_block: T::BlockNumber) -> dispatch::DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(AccountOf::<T>::is_role_correct(role), Error::<T>::InvalidData);
+            ensure!(role != PILOT_ROLE.into(), Error::<T>::NotAllowedRole);
+            ensure!(Self::account_is(&who, ADMIN_ROLE.into()), Error::<T>::NotAuthorized);
+            ensure!(until_block > <frame_system::Module<T>>::block_number(), Error::<T>::InvalidData);
+            ensure!(!UAVRegistry::<T>::contains_key(&account), Error::<T>::AddressAlreadyUsed);
+            Self::note_operation(&who)?;
+
+            let roles = AccountRegistry::<T>::get(&account).roles;
+            // Only roles, which account doesn't have yet, are reverted later
+            let granted = (roles | role) ^ roles;
+            Self::update_role_index(&account, roles, roles | role);
+            AccountRegistry::<T>::mutate(&account, |acc| {
+                acc.roles = acc.roles | role;
+                if acc.create_time.is_zero() {
+                    acc.create_time = <pallet_timestamp::Module<T>>::get();
+                }
+            });
+            TemporaryRoles::<T>::mutate(until_block, &account, |temporary| *temporary = *temporary | granted);
+            TemporaryRoleExpiries::<T>::mutate(&account, |expiries| {
+                if !expiries.contains(&until_block) {
+                    expiries.push(until_block);
+                }
+            });
+
+            Self::deposit_event(RawEvent::TemporaryRoleGranted(who, account, role, until_block));
+            Ok(())
+        }
+
+        /// Restore account registry from backup, made by `export_accounts`. Registry must be empty.
+        /// Backup should be made with the layout, which the registry currently uses.
+        #[weight = <T as Trait>::WeightInfo::import_accounts(data.len() as u32)]
+        pub fn import_accounts(origin, version: Releases, data: Vec<(T::AccountId, AccountOf<T>)>) -> dispatch::DispatchResult {
+            ensure_root(origin)?;
+            ensure!(version == StorageVersion::get(), Error::<T>::UnsupportedVersion);
+            ensure!(AccountRegistry::<T>::iter().next().is_none(), Error::<T>::InvalidAction);
+            ensure!(data.iter().all(|(_, acc)| AccountOf::<T>::is_role_correct(acc.roles)), Error::<T>::InvalidData);
+
+            let amount = data.len() as u32;
+            for (account, record) in data {
+                Self::update_role_index(&account, Zero::zero(), record.roles);
+                AccountRegistry::<T>::insert(account, record);
+            }
+            Self::deposit_event(RawEvent::AccountsImported(amount));
+            Ok(())
+        }
+
         /// Disable account entry by removing it from registry.
         /// Transaction fee for this dispatchable is made up from 3 parts.
         /// 1. base_part. is set by  frame-system::ExtrinsicBaseWeight (default value is 125000000)
@@ -314,16 +494,53 @@ decl_module! {
             // Self disabling is prohibited.
             ensure!(who != whom, Error::<T>::InvalidAction);
             // Raise error if the account doesn't exist or has been disabled already.
-            ensure!(AccountRegistry::<T>::contains_key(&whom), Error::<T>::NotExists);
+            ensure!(
+                AccountRegistry::<T>::contains_key(&whom) || ReapedAccounts::<T>::contains_key(&whom),
+                Error::<T>::NotExists
+            );
+            Self::note_operation(&who)?;
             // Delete a value from storage.
-            AccountRegistry::<T>::remove(&whom);
+            let account = if AccountRegistry::<T>::contains_key(&whom) {
+                let mut account = AccountRegistry::<T>::take(&whom);
+                Self::update_role_index(&whom, account.roles, Zero::zero());
+                // Temporary roles are dropped, they shouldn't come back with account_enable
+                let temporary = Self::cancel_temporary_roles(&whom, ALL_ROLES.into());
+                account.roles = account.roles ^ (account.roles & temporary);
+                account
+            } else {
+                // Reaped account is disabled too, otherwise it would be restored on top up.
+                ReapedAccounts::<T>::get(&whom).ok_or(Error::<T>::NotExists)?
+            };
+            ReapedAccounts::<T>::remove(&whom);
             // or just disable it by assigning 0 value to the role
             // AccountRegistry::<T>::mutate(&whom, |item|{
             // 	item.roles = NONE_ROLE;
             // });
+            DisabledAccounts::<T>::insert(&whom, account);
             Self::deposit_event(RawEvent::AccountDisabled(who, whom));
             Ok(())
         }
+
+        /// Restores account, disabled by account_disable, with its roles and metadata.
+        /// Accounts with admin role can be restored by admins only.
+        #[weight = <T as Trait>::WeightInfo::account_enable()]
+        pub fn account_enable(origin, whom: T::AccountId) -> dispatch::DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(Self::account_is(&who, (ADMIN_ROLE | REGISTRAR_ROLE).into()), Error::<T>::NotAuthorized);
+            // Enabled account, including the caller itself, has nothing to restore.
+            ensure!(!AccountRegistry::<T>::contains_key(&whom), Error::<T>::InvalidAction);
+            let account = DisabledAccounts::<T>::get(&whom).ok_or(Error::<T>::NotExists)?;
+            ensure!(
+                !account.role_is(ADMIN_ROLE.into()) || Self::account_is(&who, ADMIN_ROLE.into()),
+                Error::<T>::NotAuthorized
+            );
+            Self::note_operation(&who)?;
+            DisabledAccounts::<T>::remove(&whom);
+            Self::update_role_index(&whom, Zero::zero(), account.roles);
+            AccountRegistry::<T>::insert(&whom, account);
+            Self::deposit_event(RawEvent::AccountEnabled(who, whom));
+            Ok(())
+        }
     }
 }
 // Module allows  use  common functionality by dispatchables
@@ -334,10 +551,114 @@ impl<T: Trait> Module<T> {
     pub fn account_is(acc: &T::AccountId, role: T::AccountRole) -> bool {
         AccountRegistry::<T>::get(acc).role_is(role)
     }
+
+    /// Ensures origin is signed by a pilot, returns signer account.
+    /// Intended for flight-related calls of other pallets.
+    pub fn ensure_pilot(origin: T::Origin) -> Result<T::AccountId, dispatch::DispatchError> {
+        let who = ensure_signed(origin)?;
+        ensure!(Self::account_is(&who, PILOT_ROLE.into()), Error::<T>::NotAuthorized);
+        Ok(who)
+    }
+
+    /// Counts mutating call of an account, failing if daily limit is reached.
+    /// Other pallets call it for their own mutating calls, so the limit is shared.
+    /// Counter of previous day is overwritten, so old entries are pruned lazily.
+    pub fn note_operation(who: &T::AccountId) -> dispatch::DispatchResult {
+        let today = <frame_system::Module<T>>::block_number() / T::BlocksPerDay::get();
+        let (day, operations) = DailyOperations::<T>::get(who);
+        let operations = if day == today { operations } else { 0 };
+        ensure!(operations < T::MaxOpsPerDay::get(), Error::<T>::DailyLimitReached);
+        DailyOperations::<T>::insert(who, (today, operations + 1));
+        Ok(())
+    }
+
+    /// Get accounts with given single role, starting from `offset`.
+    /// Accounts come in storage order, which is kept between pages until the index changes.
+    /// Amount of accounts is clipped by MAX_ACCOUNTS_PER_QUERY.
+    pub fn accounts_with_role(role: T::AccountRole, offset: u32, limit: u32) -> Vec<T::AccountId> {
+        AccountsByRole::<T>::iter_prefix(role)
+            .map(|(account, _)| account)
+            .skip(offset as usize)
+            .take(limit.min(MAX_ACCOUNTS_PER_QUERY) as usize)
+            .collect()
+    }
+
+    /// Get every single role, which account has. Unknown accounts have no roles.
+    pub fn roles_of(account: &T::AccountId) -> Vec<T::AccountRole> {
+        let roles = AccountRegistry::<T>::get(account).roles;
+        SINGLE_ROLES.iter()
+            .map(|role| T::AccountRole::from(*role))
+            .filter(|role| !(roles & *role).is_zero())
+            .collect()
+    }
+
+    /// Cancels pending reverts of given roles, so they stay with the account.
+    /// Returns roles, which were granted temporarily.
+    fn cancel_temporary_roles(account: &T::AccountId, roles: T::AccountRole) -> T::AccountRole {
+        let mut cancelled = T::AccountRole::zero();
+        let mut pending = Vec::new();
+        for block in TemporaryRoleExpiries::<T>::take(account) {
+            let granted = TemporaryRoles::<T>::get(block, account);
+            let kept = granted ^ (granted & roles);
+            cancelled = cancelled | (granted & roles);
+            if kept.is_zero() {
+                TemporaryRoles::<T>::remove(block, account);
+            } else {
+                TemporaryRoles::<T>::insert(block, account, kept);
+                pending.push(block);
+            }
+        }
+        if !pending.is_empty() {
+            TemporaryRoleExpiries::<T>::insert(account, pending);
+        }
+        cancelled
+    }
+
+    /// Keep role index in sync, when roles of an account change
+    fn update_role_index(account: &T::AccountId, old_roles: T::AccountRole, new_roles: T::AccountRole) {
+        for role in SINGLE_ROLES.iter() {
+            let role: T::AccountRole = (*role).into();
+            let had_role = !(old_roles & role).is_zero();
+            let has_role = !(new_roles & role).is_zero();
+            if had_role == has_role {
+                continue;
+            }
+            if has_role {
+                AccountsByRole::<T>::insert(role, account, ());
+            } else {
+                AccountsByRole::<T>::remove(role, account);
+            }
+        }
+    }
+
+    /// Get all entries of account registry, e.g. for backup, together with their layout version
+    pub fn export_accounts() -> (Releases, Vec<(T::AccountId, AccountOf<T>)>) {
+        (StorageVersion::get(), AccountRegistry::<T>::iter().collect())
+    }
 }
 
 impl<T: Trait> OnKilledAccount<T::AccountId> for Module<T> {
     fn on_killed_account(who: &T::AccountId) {
-        AccountRegistry::<T>::remove(&who);
+        let mut account = AccountRegistry::<T>::take(&who);
+        Module::<T>::update_role_index(&who, account.roles, Zero::zero());
+        // Temporary roles are not restored on top up, their reverts would be lost meanwhile
+        let temporary = Module::<T>::cancel_temporary_roles(&who, ALL_ROLES.into());
+        account.roles = account.roles ^ (account.roles & temporary);
+        if T::AutoReenableOnTopUp::get() && account.is_enabled() {
+            ReapedAccounts::<T>::insert(&who, account);
+        }
+    }
+}
+
+impl<T: Trait> OnNewAccount<T::AccountId> for Module<T> {
+    fn on_new_account(who: &T::AccountId) {
+        if let Some(account) = ReapedAccounts::<T>::take(&who) {
+            // Account might be added again while it was reaped, newer entry wins
+            if T::AutoReenableOnTopUp::get() && !AccountRegistry::<T>::contains_key(&who) {
+                Self::update_role_index(&who, Zero::zero(), account.roles);
+                AccountRegistry::<T>::insert(&who, account);
+                Self::deposit_event(RawEvent::AccountReenabled(who.clone()));
+            }
+        }
     }
 }
@@ -57,7 +57,7 @@ impl system::Config for Test {
     type Version = ();
     type PalletInfo = PalletInfo;
     type AccountData = pallet_balances::AccountData<Balance>;
-    type OnNewAccount = ();
+    type OnNewAccount = DSAccountsModule;
     type OnKilledAccount = DSAccountsModule;
     type SystemWeightInfo = ();
     type SS58Prefix = ();
@@ -78,6 +78,9 @@ impl pallet_timestamp::Config for Test {
 // Assign module constant values
 parameter_types! {
     pub const AdminRole: u8 = super::ADMIN_ROLE;
+    pub const AutoReenableOnTopUp: bool = true;
+    pub const MaxOpsPerDay: u32 = 1000;
+    pub const BlocksPerDay: u64 = 100;
 }
 
 struct WeightInfo;
@@ -85,6 +88,9 @@ impl crate::WeightInfo for WeightInfo {
     fn account_disable() -> Weight {
         <() as crate::WeightInfo>::account_disable()
     }
+    fn account_enable() -> Weight {
+        <() as crate::WeightInfo>::account_enable()
+    }
     fn account_add() -> Weight {
         <() as crate::WeightInfo>::account_add()
     }
@@ -95,6 +101,12 @@ impl crate::WeightInfo for WeightInfo {
     fn register_uav() -> Weight {
         <() as crate::WeightInfo>::register_uav()
     }
+    fn grant_temporary_role() -> Weight {
+        <() as crate::WeightInfo>::grant_temporary_role()
+    }
+    fn import_accounts(records: u32) -> Weight {
+        <() as crate::WeightInfo>::import_accounts(records)
+    }
 }
 
 impl Trait for Test {
@@ -105,6 +117,9 @@ impl Trait for Test {
     type WeightInfo = ();
     type SerialNumber = Vec<u8>;
     type MetaIPFS = Vec<u8>;    //not sure which type use here, for simplicity will be string
+    type AutoReenableOnTopUp = AutoReenableOnTopUp;
+    type MaxOpsPerDay = MaxOpsPerDay;
+    type BlocksPerDay = BlocksPerDay;
 }
 
 parameter_types! {
@@ -1,6 +1,8 @@
 use crate::mock::*;
 use frame_support::{
     assert_noop, assert_ok,
+    storage::StoragePrefixedMap,
+    traits::OnInitialize,
 };
 
 // Learn more about testing substrate runtime modules
@@ -107,6 +109,136 @@ fn it_try_disable_themself() {
     });
 }
 
+#[test]
+fn it_enable_disabled_account() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_2_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        let account = DSAccountsModule::account_registry(REGISTRAR_2_ACCOUNT_ID);
+        assert_ok!(DSAccountsModule::account_disable(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID
+        ));
+        assert_ok!(DSAccountsModule::account_enable(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID
+        ));
+        assert!(DSAccountsModule::account_registry(REGISTRAR_2_ACCOUNT_ID).is_enabled());
+        assert_eq!(DSAccountsModule::account_registry(REGISTRAR_2_ACCOUNT_ID), account);
+        let mut registrars = DSAccountsModule::accounts_with_role(super::REGISTRAR_ROLE, 0, 10);
+        registrars.sort();
+        assert_eq!(registrars, vec![REGISTRAR_1_ACCOUNT_ID, REGISTRAR_2_ACCOUNT_ID]);
+    });
+}
+
+#[test]
+fn it_try_enable_enabled_or_unknown_account() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_noop!(
+            DSAccountsModule::account_enable(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID
+            ),
+            Error::InvalidAction
+        );
+        assert_noop!(
+            DSAccountsModule::account_enable(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID
+            ),
+            Error::NotExists
+        );
+        assert_noop!(
+            DSAccountsModule::account_enable(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID
+            ),
+            Error::NotExists
+        );
+        assert_noop!(
+            DSAccountsModule::account_enable(
+                Origin::signed(PILOT_1_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID
+            ),
+            Error::NotAuthorized
+        );
+    });
+}
+
+#[test]
+fn it_enable_admin_only_by_admin() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_2_ACCOUNT_ID,
+            super::ADMIN_ROLE
+        ));
+        assert_ok!(DSAccountsModule::account_disable(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID
+        ));
+        assert_noop!(
+            DSAccountsModule::account_enable(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID
+            ),
+            Error::NotAuthorized
+        );
+        assert_ok!(DSAccountsModule::account_enable(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID
+        ));
+        assert!(DSAccountsModule::account_is(&REGISTRAR_2_ACCOUNT_ID, super::ADMIN_ROLE));
+    });
+}
+
+#[test]
+fn it_drop_temporary_roles_of_disabled_account() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_ok!(DSAccountsModule::grant_temporary_role(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::ADMIN_ROLE,
+            10
+        ));
+        assert_ok!(DSAccountsModule::account_disable(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID
+        ));
+        DSAccountsModule::on_initialize(10);
+        assert_ok!(DSAccountsModule::account_enable(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID
+        ));
+        assert!(DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::REGISTRAR_ROLE));
+        assert!(!DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::ADMIN_ROLE));
+    });
+}
+
 #[test]
 fn it_try_create_account_with_role_pilot() {
     new_test_ext().execute_with(|| {
@@ -398,4 +530,303 @@ fn it_try_register_user_on_uav_addr() {
             Error::AddressAlreadyUsed
         );
     });
-}
\ No newline at end of file
+}
+#[test]
+fn it_grant_temporary_role() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_ok!(DSAccountsModule::grant_temporary_role(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::ADMIN_ROLE,
+            10
+        ));
+        assert!(DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::ADMIN_ROLE));
+
+        DSAccountsModule::on_initialize(9);
+        assert!(DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::ADMIN_ROLE));
+
+        DSAccountsModule::on_initialize(10);
+        assert!(!DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::ADMIN_ROLE));
+        // Role, which account had before, is kept
+        assert!(DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::REGISTRAR_ROLE));
+    });
+}
+
+#[test]
+fn it_keep_temporary_role_granted_permanently() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_ok!(DSAccountsModule::grant_temporary_role(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::ADMIN_ROLE,
+            10
+        ));
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::ADMIN_ROLE | super::REGISTRAR_ROLE
+        ));
+        assert!(DSAccountsModule::temporary_role_expiries(REGISTRAR_1_ACCOUNT_ID).is_empty());
+
+        DSAccountsModule::on_initialize(10);
+        assert!(DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::ADMIN_ROLE));
+        assert!(DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::REGISTRAR_ROLE));
+    });
+}
+
+#[test]
+fn it_try_grant_temporary_role_not_by_admin() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_noop!(
+            DSAccountsModule::grant_temporary_role(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::ADMIN_ROLE,
+                10
+            ),
+            Error::NotAuthorized
+        );
+        assert_noop!(
+            DSAccountsModule::grant_temporary_role(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::ADMIN_ROLE,
+                1
+            ),
+            Error::InvalidData
+        );
+    });
+}
+
+#[test]
+fn it_export_and_import_accounts() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(5000);
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_ok!(DSAccountsModule::register_pilot(
+            Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+            PILOT_1_ACCOUNT_ID,
+            vec![0],
+        ));
+        let (version, mut backup) = DSAccountsModule::export_accounts();
+        assert_eq!(version, super::Releases::V1);
+        assert_eq!(backup.len(), 3);
+
+        // Registry must be empty before import
+        assert_noop!(
+            DSAccountsModule::import_accounts(Origin::root(), version, backup.clone()),
+            Error::InvalidAction
+        );
+        super::AccountRegistry::<Test>::remove_all();
+        assert!(DSAccountsModule::export_accounts().1.is_empty());
+
+        assert_ok!(DSAccountsModule::import_accounts(Origin::root(), version, backup.clone()));
+        let (_, mut restored) = DSAccountsModule::export_accounts();
+        backup.sort_by_key(|(account, _)| *account);
+        restored.sort_by_key(|(account, _)| *account);
+        assert_eq!(restored, backup);
+        assert!(DSAccountsModule::account_is(&PILOT_1_ACCOUNT_ID, super::PILOT_ROLE));
+        assert!(DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::REGISTRAR_ROLE));
+    });
+}
+
+#[test]
+fn it_reenable_reaped_account_on_top_up() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Balances::transfer(
+                Origin::signed(ADMIN_ACCOUNT_ID), 
+                REGISTRAR_1_ACCOUNT_ID, 
+                10000)
+        );
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_ok!(Balances::transfer(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID), 
+                3, 
+                10000
+        ));
+        assert!(!DSAccountsModule::account_registry(REGISTRAR_1_ACCOUNT_ID).is_enabled());
+
+        assert_ok!(Balances::transfer(
+                Origin::signed(ADMIN_ACCOUNT_ID), 
+                REGISTRAR_1_ACCOUNT_ID, 
+                10000)
+        );
+        assert!(DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::REGISTRAR_ROLE));
+        assert!(System::events().iter().any(|record| 
+            record.event == Event::pallet_ds_accounts(super::RawEvent::AccountReenabled(REGISTRAR_1_ACCOUNT_ID))
+        ));
+        assert!(DSAccountsModule::reaped_account(REGISTRAR_1_ACCOUNT_ID).is_none());
+    });
+}
+
+#[test]
+fn it_drop_temporary_roles_of_reaped_account() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Balances::transfer(
+                Origin::signed(ADMIN_ACCOUNT_ID), 
+                REGISTRAR_1_ACCOUNT_ID, 
+                10000)
+        );
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_ok!(DSAccountsModule::grant_temporary_role(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::ADMIN_ROLE,
+            10
+        ));
+        assert_ok!(Balances::transfer(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID), 
+                3, 
+                10000
+        ));
+        DSAccountsModule::on_initialize(10);
+
+        assert_ok!(Balances::transfer(
+                Origin::signed(ADMIN_ACCOUNT_ID), 
+                REGISTRAR_1_ACCOUNT_ID, 
+                10000)
+        );
+        assert!(DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::REGISTRAR_ROLE));
+        assert!(!DSAccountsModule::account_is(&REGISTRAR_1_ACCOUNT_ID, super::ADMIN_ROLE));
+    });
+}
+
+#[test]
+fn it_disable_reaped_account() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::transfer(
+                Origin::signed(ADMIN_ACCOUNT_ID), 
+                REGISTRAR_1_ACCOUNT_ID, 
+                10000)
+        );
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::ADMIN_ROLE
+        ));
+        assert_ok!(Balances::transfer(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID), 
+                3, 
+                10000
+        ));
+        assert!(DSAccountsModule::reaped_account(REGISTRAR_1_ACCOUNT_ID).is_some());
+
+        assert_ok!(DSAccountsModule::account_disable(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID
+        ));
+        assert!(DSAccountsModule::reaped_account(REGISTRAR_1_ACCOUNT_ID).is_none());
+        assert_ok!(Balances::transfer(
+                Origin::signed(ADMIN_ACCOUNT_ID), 
+                REGISTRAR_1_ACCOUNT_ID, 
+                10000)
+        );
+        assert!(!DSAccountsModule::account_registry(REGISTRAR_1_ACCOUNT_ID).is_enabled());
+    });
+}
+
+#[test]
+fn it_list_accounts_by_role() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(DSAccountsModule::accounts_with_role(super::ADMIN_ROLE, 0, 10), vec![ADMIN_ACCOUNT_ID]);
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_ok!(DSAccountsModule::register_pilot(
+            Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+            PILOT_1_ACCOUNT_ID,
+            vec![0],
+        ));
+        assert_eq!(DSAccountsModule::accounts_with_role(super::PILOT_ROLE, 0, 10), vec![PILOT_1_ACCOUNT_ID]);
+        assert_eq!(DSAccountsModule::accounts_with_role(super::REGISTRAR_ROLE, 0, 10), vec![REGISTRAR_1_ACCOUNT_ID]);
+        assert!(DSAccountsModule::accounts_with_role(super::PILOT_ROLE, 1, 10).is_empty());
+
+        assert_ok!(DSAccountsModule::account_disable(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            PILOT_1_ACCOUNT_ID
+        ));
+        assert!(DSAccountsModule::accounts_with_role(super::PILOT_ROLE, 0, 10).is_empty());
+    });
+}
+
+#[test]
+fn it_get_roles_of_account() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(DSAccountsModule::roles_of(&ADMIN_ACCOUNT_ID), vec![super::ADMIN_ROLE]);
+        assert!(DSAccountsModule::roles_of(&REGISTRAR_2_ACCOUNT_ID).is_empty());
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::ADMIN_ROLE | super::REGISTRAR_ROLE
+        ));
+        assert_ok!(DSAccountsModule::register_pilot(
+            Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            vec![0],
+        ));
+        assert_eq!(
+            DSAccountsModule::roles_of(&REGISTRAR_1_ACCOUNT_ID),
+            vec![super::ADMIN_ROLE, super::PILOT_ROLE, super::REGISTRAR_ROLE]
+        );
+        let mut admins = DSAccountsModule::accounts_with_role(super::ADMIN_ROLE, 0, 10);
+        admins.sort();
+        assert_eq!(admins, vec![ADMIN_ACCOUNT_ID, REGISTRAR_1_ACCOUNT_ID]);
+        assert_eq!(DSAccountsModule::accounts_with_role(super::PILOT_ROLE, 0, 10), vec![REGISTRAR_1_ACCOUNT_ID]);
+    });
+}
+
+#[test]
+fn it_ensure_pilot() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+        ));
+        assert_ok!(DSAccountsModule::register_pilot(
+            Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+            PILOT_1_ACCOUNT_ID,
+            vec![0],
+        ));
+        assert_eq!(DSAccountsModule::ensure_pilot(Origin::signed(PILOT_1_ACCOUNT_ID)), Ok(PILOT_1_ACCOUNT_ID));
+        assert_noop!(
+            DSAccountsModule::ensure_pilot(Origin::signed(REGISTRAR_1_ACCOUNT_ID)),
+            Error::NotAuthorized
+        );
+        assert!(DSAccountsModule::ensure_pilot(Origin::root()).is_err());
+    });
+}
@@ -0,0 +1,119 @@
+//! RPC interface for the DS maps pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{de::DeserializeOwned, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_ds_maps_runtime_api::DSMapsApi as DSMapsRuntimeApi;
+use pallet_ds_maps_runtime_api::{Box3D, Point3D, Zone};
+
+/// Error code, returned when runtime API call fails
+const RUNTIME_ERROR: i64 = 1;
+
+#[rpc]
+pub trait DSMapsApi<BlockHash, Coord> {
+    /// Zones, which boxes intersect given region, starting from `offset`.
+    /// Result is capped by the runtime, use offset to fetch the next page.
+    /// Null means the region is too large to be scanned, split it into smaller ones.
+    #[rpc(name = "ds_maps_zonesInRegion")]
+    fn zones_in_region(
+        &self,
+        region: Box3D<Coord>,
+        offset: u32,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> Result<Option<Vec<Zone<Coord>>>>;
+
+    /// Ids of red zones, which straight flight segment would cross.
+    /// Allows validating routes before submitting them. Null means the segment can't be
+    /// checked, e.g. it leaves the mapped area, so it shouldn't be considered clear.
+    #[rpc(name = "ds_maps_checkSegment")]
+    fn check_segment(
+        &self,
+        from: Point3D<Coord>,
+        to: Point3D<Coord>,
+        at: Option<BlockHash>,
+    ) -> Result<Option<Vec<u128>>>;
+
+    /// True, if boxes of two zones overlap, null if any of them doesn't exist.
+    #[rpc(name = "ds_maps_zonesConflict")]
+    fn zones_conflict(
+        &self,
+        a: u128,
+        b: u128,
+        at: Option<BlockHash>,
+    ) -> Result<Option<bool>>;
+}
+
+/// Implements DSMapsApi by calling into the runtime
+pub struct DSMaps<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> DSMaps<C, B> {
+    pub fn new(client: Arc<C>) -> Self {
+        DSMaps { client, _marker: Default::default() }
+    }
+}
+
+impl<C, Block, Coord> DSMapsApi<<Block as BlockT>::Hash, Coord> for DSMaps<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: DSMapsRuntimeApi<Block, Coord>,
+    Coord: Codec + Send + Sync + 'static + Serialize + DeserializeOwned,
+{
+    fn zones_in_region(
+        &self,
+        region: Box3D<Coord>,
+        offset: u32,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<Vec<Zone<Coord>>>> {
+        let api = self.client.runtime_api();
+        // Use best block, if it wasn't specified
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.zones_in_region(&at, region, offset, limit).map_err(|e| RpcError {
+            code: ErrorCode::ServerError(RUNTIME_ERROR),
+            message: "Unable to query zones in region.".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+
+    fn check_segment(
+        &self,
+        from: Point3D<Coord>,
+        to: Point3D<Coord>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<Vec<u128>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.check_segment(&at, from, to).map_err(|e| RpcError {
+            code: ErrorCode::ServerError(RUNTIME_ERROR),
+            message: "Unable to check segment.".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+
+    fn zones_conflict(
+        &self,
+        a: u128,
+        b: u128,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<bool>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.zones_conflict(&at, a, b).map_err(|e| RpcError {
+            code: ErrorCode::ServerError(RUNTIME_ERROR),
+            message: "Unable to check zones conflict.".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+}
@@ -0,0 +1,28 @@
+//! Runtime API definition for the DS maps pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` and `unnecessary_mut_passed` warnings come from the macro expansion
+#![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_ds_maps::{Box3D, Point3D, Zone};
+
+sp_api::decl_runtime_apis! {
+    pub trait DSMapsApi<Coord> where
+        Coord: Codec,
+    {
+        /// Zones, which boxes intersect given region, starting from `offset`.
+        /// Amount of zones is clipped by the pallet's MAX_ZONES_PER_QUERY.
+        /// None, if the region covers too many bitmap cells or areas to be scanned.
+        fn zones_in_region(region: Box3D<Coord>, offset: u32, limit: u32) -> Option<Vec<Zone<Coord>>>;
+
+        /// Ids of enforced zones, which straight segment between `from` and `to` passes through.
+        /// None, if the segment can't be checked, e.g. it starts or ends outside of all roots.
+        fn check_segment(from: Point3D<Coord>, to: Point3D<Coord>) -> Option<Vec<u128>>;
+
+        /// True, if boxes of two zones overlap, None if any of them doesn't exist.
+        fn zones_conflict(a: u128, b: u128) -> Option<bool>;
+    }
+}
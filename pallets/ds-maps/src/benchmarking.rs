@@ -0,0 +1,88 @@
+//! Benchmarking setup for pallet-ds-maps
+
+use super::*;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+
+fn coord<T: Trait>(value: &str) -> T::Coord {
+    T::Coord::from_str(value).ok().expect("benchmark coordinates are valid")
+}
+
+/// Gives registrar role to the account, using admin from genesis
+fn make_registrar<T: Trait>(who: &T::AccountId) {
+    let admin = <accounts::Module<T>>::accounts_with_role(ADMIN_ROLE.into(), 0, 1)
+        .pop()
+        .expect("genesis has an admin account");
+    <accounts::Module<T>>::account_add(RawOrigin::Signed(admin).into(), who.clone(), REGISTRAR_ROLE.into())
+        .expect("admin can add registrar");
+}
+
+/// Adds the same root, as in tests, returns its id
+fn add_root<T: Trait>(who: &T::AccountId) -> RootId {
+    let bounding_box = Box3D::new(
+        Point3D::new(coord::<T>("55.371"), coord::<T>("37.371"), coord::<T>("1")),
+        Point3D::new(coord::<T>("55.921"), coord::<T>("37.901"), coord::<T>("3")),
+    );
+    Module::<T>::root_add(RawOrigin::Signed(who.clone()).into(), bounding_box, coord::<T>("0.01"))
+        .expect("root fits the map");
+    RootBoxes::<T>::iter().next().map(|(root_id, _)| root_id).expect("root is stored")
+}
+
+/// Zone number `index` in the same area. Zones are thin stripes, so they don't overlap
+fn stripe<T: Trait>(index: u32) -> Rect2D<T::Coord> {
+    let step = coord::<T>("0.00009");
+    let south = coord::<T>("55.3915") + step * T::Coord::from_int(index);
+    Rect2D::new(
+        Point2D::new(south, coord::<T>("37.3915")),
+        Point2D::new(south + coord::<T>("0.00005"), coord::<T>("37.3925")),
+    )
+}
+
+benchmarks! {
+    // Overlap check scans zones of the area, so cost grows with amount of them
+    zone_add {
+        let z in 0 .. T::MaxBuildingsInArea::get() as u32 - 1;
+        let caller: T::AccountId = whitelisted_caller();
+        make_registrar::<T>(&caller);
+        let root_id = add_root::<T>(&caller);
+        for index in 0..z {
+            Module::<T>::zone_add(RawOrigin::Signed(caller.clone()).into(), stripe::<T>(index), 10, root_id)?;
+            // Let the area settle, so RegionCooldownBlocks doesn't block the next zone
+            let settled = <frame_system::Module<T>>::block_number() + T::RegionCooldownBlocks::get();
+            <frame_system::Module<T>>::set_block_number(settled);
+        }
+    }: _(RawOrigin::Signed(caller), stripe::<T>(z), 10, root_id)
+    verify {
+        assert_eq!(TotalZones::get(), z + 1);
+    }
+
+    // Batched zones share an area, so each of them scans zones, added before it
+    zone_add_batch {
+        let n in 1 .. T::MaxZonesPerBatch::get().min(T::MaxBuildingsInArea::get() as u32);
+        let caller: T::AccountId = whitelisted_caller();
+        make_registrar::<T>(&caller);
+        let root_id = add_root::<T>(&caller);
+        let zones: Vec<(Rect2D<T::Coord>, LightCoord)> = (0..n).map(|index| (stripe::<T>(index), 10)).collect();
+    }: _(RawOrigin::Signed(caller), zones, root_id)
+    verify {
+        assert_eq!(TotalZones::get(), n);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        // Each benchmark adds the same root, so they run on separate storages
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_zone_add::<Test>());
+        });
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_zone_add_batch::<Test>());
+        });
+    }
+}
@@ -4,14 +4,25 @@ impl crate::WeightInfo for () {
     fn root_add() -> Weight {
         1_000_000_u64.saturating_add(DbWeight::get().writes(1))
     }
-    fn zone_add() -> Weight {
-        1_000_000_u64.saturating_add(DbWeight::get().writes(1))
+    // Storage accesses follow zone_add benchmark. Reads: caller role, root, map bounds, zone counter,
+    // area, z zone slots, area change block, daily operations and area density.
+    // Writes: area, zone, author and its index, counter, density with two index entries,
+    // creation block with its index, area change block and daily operations.
+    fn zone_add(z: u32) -> Weight {
+        1_000_000_u64
+            .saturating_add(50_000_u64.saturating_mul(z as Weight))
+            .saturating_add(DbWeight::get().reads_writes(z as Weight + 8, 12))
     }
+    // Zones of the root are charged by the call, through purge_zone weight.
+    // Reads: caller role, root, daily operations and up to 4 bitmap pages.
+    // Writes: daily operations, pages, root and area change blocks.
     fn root_remove() -> Weight {
-        1_000_000_u64.saturating_add(DbWeight::get().writes(1))
+        1_000_000_u64.saturating_add(DbWeight::get().reads_writes(7, 7))
     }   
+    // Zone records are charged by the call, through purge_zone weight.
+    // Reads: caller role, zone, author and daily operations. Writes: daily operations.
     fn zone_remove() -> Weight {
-        1_000_000_u64.saturating_add(DbWeight::get().writes(1))
+        1_000_000_u64.saturating_add(DbWeight::get().reads_writes(4, 1))
     }   
     fn change_area_type() -> Weight {
         100_000_u64.saturating_add(DbWeight::get().writes(1))
@@ -19,4 +30,30 @@ impl crate::WeightInfo for () {
     fn route_add() -> Weight {
         100_000_u64.saturating_add(DbWeight::get().writes(1))
     }
+    fn set_coordinate_system() -> Weight {
+        100_000_u64.saturating_add(DbWeight::get().writes(1))
+    }
+    fn zone_attest() -> Weight {
+        100_000_u64.saturating_add(DbWeight::get().reads_writes(1, 1))
+    }
+    fn zone_set_frozen() -> Weight {
+        100_000_u64.saturating_add(DbWeight::get().reads_writes(1, 1))
+    }
+    // Storage accesses follow zone_add_batch benchmark. Caller role is read once,
+    // every zone costs the rest of zone_add accesses
+    fn zone_add_batch(zones: u32, z: u32) -> Weight {
+        let per_zone = 1_000_000_u64
+            .saturating_add(50_000_u64.saturating_mul(z as Weight))
+            .saturating_add(DbWeight::get().reads_writes(z as Weight + 7, 12));
+        1_000_000_u64
+            .saturating_add(DbWeight::get().reads(1))
+            .saturating_add((zones as Weight).saturating_mul(per_zone))
+    }
+    fn zone_soft_delete() -> Weight {
+        100_000_u64.saturating_add(DbWeight::get().reads_writes(4, 5))
+    }
+
+    fn map_init() -> Weight {
+        100_000_u64.saturating_add(DbWeight::get().reads_writes(1, 1))
+    }
 }
@@ -5,29 +5,36 @@ use serde::{Deserialize, Serialize};
 
 use frame_support::{
     codec::{Decode, Encode},
-    storage::StorageDoubleMap,
+    storage::{StorageDoubleMap, IterableStorageMap, IterableStorageDoubleMap},
     dispatch::fmt::Debug,
     sp_runtime::sp_std::{ops::{Sub, Div, Mul, Add}, vec::Vec},
-    decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,    
+    decl_error, decl_event, decl_module, decl_storage, dispatch, ensure, transactional,
     weights::Weight,
     Parameter,
     traits::Get,
+    sp_runtime::Permill,
 };
+use frame_support::sp_runtime::traits::{Zero, One};
 
 use sp_std::{
     str::FromStr,
     marker::PhantomData,
     vec, 
     mem::swap,
-    cmp::{max, min}
+    cmp::{max, min, Ordering},
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
 };
 
-use dsky_utils::{CastToType, FromRaw, IntDiv, Signed, ToBigCoord, FromBigCoord, GetEpsilon};
-use frame_system::ensure_signed;
+use dsky_utils::{CastToType, FromRaw, IntDiv, Signed, ToBigCoord, FromBigCoord, GetEpsilon, FromInt};
+use frame_system::{ensure_signed, ensure_root};
 use pallet_ds_accounts as accounts;
-use accounts::{REGISTRAR_ROLE, PILOT_ROLE};
+use accounts::{ADMIN_ROLE, REGISTRAR_ROLE, PILOT_ROLE};
 
 mod default_weight;
+pub mod migrations;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -40,6 +47,27 @@ pub const MAX_PAGES_AMOUNT_TO_EXTRACT: u32 = 4;
 pub const PAGE_LENGTH: u32 = 32;
 pub const PAGE_WIDTH: u32 = 50;
 
+/// Max amount of areas, which can be inspected by a single read query
+pub const MAX_AREAS_PER_QUERY: u16 = 1024;
+/// Max amount of landing spots, returned by a single query
+pub const MAX_LANDING_SPOTS: u32 = 64;
+/// Max amount of points, checked by a single restriction bitmap query
+pub const MAX_POINTS_PER_QUERY: usize = 256;
+/// Max amount of bitmap cells, which bounding rectangle of a checked segment or region may cover
+pub const MAX_CELLS_PER_QUERY: u32 = 1024;
+/// Max amount of zones, returned by a single region query
+pub const MAX_ZONES_PER_QUERY: u32 = 256;
+/// Max amount of blocks, which can be inspected by a single block range query
+pub const MAX_BLOCKS_PER_QUERY: u32 = 1024;
+/// Max amount of zone authors, returned by a single query
+pub const MAX_AUTHORS_PER_QUERY: u32 = 256;
+/// Max shift in box sizes, at which suggested box for a conflicting one is searched
+pub const SUGGESTION_SEARCH_RADIUS: i32 = 2;
+/// Extra cost of passing through an area for each zone inside, used in path search
+pub const ZONE_PATH_COST: u32 = 10;
+/// Max multiplier of direction vector, at which projected segments are searched
+pub const MAX_PROJECTION_SCALE: u32 = 16;
+
 /// Bitmap cell parameters in degree e-2
 const BITMAP_CELL_LENGTH: u32 = 1;
 const BITMAP_CELL_WIDTH: u32 = 1;
@@ -171,11 +199,18 @@ pub struct Zone<Coord> {
     pub zone_id: ZoneId,
     pub rect: Rect2D<Coord>,
     pub height: LightCoord,
+    /// Human readable label, may be empty
+    pub name: Vec<u8>,
 }
 
 impl<Coord> Zone<Coord> {
     pub fn new(zone_id: ZoneId, rect: Rect2D<Coord>, height: LightCoord) -> Self {
-        Zone {zone_id, rect, height}
+        Zone {zone_id, rect, height, name: Vec::new()}
+    }
+
+    pub fn with_name(mut self, name: Vec<u8>) -> Self {
+        self.name = name;
+        self
     }
 } 
 
@@ -219,6 +254,82 @@ impl<
         let north_east = self.north_east.project();
         Rect2D::new(south_west, north_east)
     }
+
+    /// True, if boxes overlap on each axis. Touching faces are not an overlap.
+    pub fn intersects_box(self, target: Box3D<Coord>) -> bool {
+        !(self.north_east.lat <= target.south_west.lat ||
+          self.south_west.lat >= target.north_east.lat ||
+          self.north_east.lon <= target.south_west.lon ||
+          self.south_west.lon >= target.north_east.lon ||
+          self.north_east.alt <= target.south_west.alt ||
+          self.south_west.alt >= target.north_east.alt)
+    }
+
+    /// True, if point lies inside the box. Lower bounds are inclusive, upper are exclusive,
+    /// so a point on a shared face belongs to exactly one of adjacent boxes.
+    pub fn contains_point(&self, point: Point3D<Coord>) -> bool {
+        self.south_west.lat <= point.lat && point.lat < self.north_east.lat &&
+        self.south_west.lon <= point.lon && point.lon < self.north_east.lon &&
+        self.south_west.alt <= point.alt && point.alt < self.north_east.alt
+    }
+
+    /// True, if target box lies inside this one. Touching faces from inside are allowed.
+    pub fn contains_box(&self, target: Box3D<Coord>) -> bool {
+        self.south_west.lat <= target.south_west.lat && target.north_east.lat <= self.north_east.lat &&
+        self.south_west.lon <= target.south_west.lon && target.north_east.lon <= self.north_east.lon &&
+        self.south_west.alt <= target.south_west.alt && target.north_east.alt <= self.north_east.alt
+    }
+}
+
+impl<
+    Coord: Copy + Sub<Output = Coord> + Add<Output = Coord> + Div<Output = Coord> + FromInt + ToBigCoord<Output = BigCoord>,
+    BigCoord: Mul<Output = BigCoord> + Add<Output = BigCoord>
+    > Box3D<Coord> {
+    /// Gets center and squared radius of the sphere, enclosing the box.
+    /// Radius is returned squared to avoid sqrt on-chain, and as BigCoord, so it doesn't overflow Coord.
+    pub fn bounding_sphere(&self) -> (Point3D<Coord>, BigCoord) {
+        let two = Coord::from_int(2);
+        let half_lat = (self.north_east.lat - self.south_west.lat) / two;
+        let half_lon = (self.north_east.lon - self.south_west.lon) / two;
+        let half_alt = (self.north_east.alt - self.south_west.alt) / two;
+        let center = Point3D {
+            lat: self.south_west.lat + half_lat,
+            lon: self.south_west.lon + half_lon,
+            alt: self.south_west.alt + half_alt,
+        };
+        let squared_radius = half_lat.try_into() * half_lat.try_into() +
+                             half_lon.try_into() * half_lon.try_into() +
+                             half_alt.try_into() * half_alt.try_into();
+        (center, squared_radius)
+    }
+}
+
+#[cfg(test)]
+mod box_tests {
+    use super::*;
+    use crate::tests::{coord, Coord};
+    use substrate_fixed::types::I42F22;
+
+    #[test]
+    fn bounding_sphere_of_unit_cube() {
+        let cube: Box3D<Coord> = Box3D::new(Point3D::new(coord("0"), coord("0"), coord("0")),
+                                            Point3D::new(coord("1"), coord("1"), coord("1")));
+        let (center, squared_radius) = cube.bounding_sphere();
+        assert_eq!(center, Point3D::new(coord("0.5"), coord("0.5"), coord("0.5")));
+        assert_eq!(squared_radius, coord::<I42F22>("0.75"));
+    }
+
+    #[test]
+    fn boxes_overlap_only_on_every_axis() {
+        let cube: Box3D<Coord> = Box3D::new(Point3D::new(coord("0"), coord("0"), coord("0")),
+                                            Point3D::new(coord("2"), coord("2"), coord("2")));
+        let inner = Box3D::new(Point3D::new(coord("1"), coord("1"), coord("1")),
+                               Point3D::new(coord("3"), coord("3"), coord("3")));
+        let above = Box3D::new(Point3D::new(coord("1"), coord("1"), coord("2")),
+                               Point3D::new(coord("3"), coord("3"), coord("3")));
+        assert!(cube.intersects_box(inner));
+        assert!(!cube.intersects_box(above));
+    }
 }
 
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -573,7 +684,7 @@ pub struct RootBox<Coord> {
 }
 
 impl<
-    Coord: PartialOrd + Sub<Output = Coord> + Signed + IntDiv + Mul<Output = Coord> + Div<Output = Coord> + Copy,
+    Coord: PartialOrd + Sub<Output = Coord> + Signed + IntDiv + Mul<Output = Coord> + Div<Output = Coord> + Copy + Default,
     > RootBox<Coord> {
     pub fn new(id: RootId, bounding_box: Box3D<Coord>, delta: Coord) -> Self {
         RootBox{id, bounding_box, delta}
@@ -628,6 +739,48 @@ impl<
         (total_rows * (column - 1)) + row
     }
 
+    /// Returns ids of areas in root, which intersect given rect. Areas, which rect touches only
+    /// by north or east edge, might be included too. None, if there are more than `max_areas` of them.
+    fn areas_in_rect(self, rect: Rect2D<Coord>, max_areas: u16) -> Option<Vec<AreaId>> {
+        let root_projection = self.bounding_box.projection_on_plane();
+        if !root_projection.intersects_rect(rect) {
+            return Some(Vec::new());
+        }
+        let root_dimensions = root_projection.get_dimensions();
+        let total_rows = root_dimensions.lat.integer_division_u16(self.delta);
+        let total_columns = root_dimensions.lon.integer_division_u16(self.delta);
+        let origin = root_projection.south_west;
+        let end = root_projection.north_east;
+        // Rect is clipped by the root first, so the distances fit into u16 areas
+        let distance = |value: Coord, low: Coord, high: Coord| {
+            if value <= low {
+                Coord::default()
+            } else if value >= high {
+                high - low
+            } else {
+                value - low
+            }
+        };
+        let first_row = distance(rect.south_west.lat, origin.lat, end.lat).integer_division_u16(self.delta) + 1;
+        let first_column = distance(rect.south_west.lon, origin.lon, end.lon).integer_division_u16(self.delta) + 1;
+        let last_row = min(distance(rect.north_east.lat, origin.lat, end.lat).integer_division_u16(self.delta) + 1, total_rows);
+        let last_column = min(distance(rect.north_east.lon, origin.lon, end.lon).integer_division_u16(self.delta) + 1, total_columns);
+        if first_row > last_row || first_column > last_column {
+            return Some(Vec::new());
+        }
+        let areas = (last_row - first_row + 1) as u32 * (last_column - first_column + 1) as u32;
+        if areas > max_areas as u32 {
+            return None;
+        }
+        let mut output = Vec::new();
+        for column in first_column..=last_column {
+            for row in first_row..=last_row {
+                output.push(total_rows * (column - 1) + row);
+            }
+        }
+        Some(output)
+    }
+
     #[cfg(test)]
     pub fn is_active(&self) -> bool {
         self.id != 0
@@ -637,7 +790,7 @@ impl<
 #[cfg(test)]
 mod rootbox_tests {
     use super::*;
-    use crate::tests::{construct_custom_box, ROOT_ID, coord, Coord};
+    use crate::tests::{construct_custom_box, construct_custom_rect, ROOT_ID, coord, Coord};
 
     #[test]
     fn max_area_small_root() {
@@ -690,6 +843,22 @@ mod rootbox_tests {
         assert_eq!(root.detect_intersected_area(out_point), 0);
     }
 
+    #[test]
+    fn areas_in_rect_are_clipped_by_root() {
+        let bbox = construct_custom_box("0", "0", "2", "3");
+        let root = RootBox::new(100, bbox, coord("1"));
+
+        let rect = construct_custom_rect("0.5", "0.5", "1.5", "1.5");
+        assert_eq!(root.areas_in_rect(rect, 4), Some(vec![1, 2, 3, 4]));
+        assert_eq!(root.areas_in_rect(rect, 3), None);
+
+        let overhanging = construct_custom_rect("-5", "2.5", "0.5", "50");
+        assert_eq!(root.areas_in_rect(overhanging, 4), Some(vec![5]));
+
+        let outside = construct_custom_rect("10", "10", "11", "11");
+        assert_eq!(root.areas_in_rect(outside, 4), Some(Vec::new()));
+    }
+
     #[test]
     fn extract_values_from_rootbox_index() {
         let rootbox_sw_cell_row: u64 = 0b0000_0000_0000_0101;
@@ -818,6 +987,86 @@ impl Area {
     } 
 }
 
+/// Versions of stored data layout, used by migrations
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Releases {
+    /// Zones without names
+    V1,
+    /// Zones carry a name
+    V2,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
+    }
+}
+
+/// Summary of zones, intersecting some region
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionStats {
+    /// Zones, which restrict airspace
+    pub enforced_zones: u32,
+    /// Zones, which enforcement is suspended
+    pub frozen_zones: u32,
+    /// Sum of whole volumes of the zones, including parts outside of the region.
+    /// Counted same way as overlap_volume, saturates at u128::MAX.
+    pub covered_volume: u128,
+    /// Amount of distinct accounts, which added the zones
+    pub owners: u32,
+}
+
+/// Defines how much data zone events carry. Richer events help indexers, but cost space.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventVerbosity {
+    /// Only zone id is emitted: [zone number]
+    Minimal,
+    /// Zone ids with the actor, creation also carries zone geometry: [root, area, zone number, who, rect, height]
+    Standard,
+    /// Standard fields plus zone geometry on removal and zone name on creation
+    Full,
+}
+
+impl Default for EventVerbosity {
+    fn default() -> Self {
+        EventVerbosity::Standard
+    }
+}
+
+/// Describes, how clients should interpret coordinates stored on the map
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateSystem {
+    /// Meters from some local origin
+    LocalMeters,
+    /// WGS84 latitude and longitude in degrees, stored as fixed point numbers
+    ScaledWGS84,
+}
+
+impl Default for CoordinateSystem {
+    fn default() -> Self {
+        CoordinateSystem::ScaledWGS84
+    }
+}
+
+/// Defines how queries treat points, which lie outside of all roots
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutOfWorldPolicy {
+    /// Nothing is known about such points, so they are free
+    Unrestricted,
+    /// Unmapped airspace is forbidden, the safe choice
+    Restricted,
+}
+
+impl Default for OutOfWorldPolicy {
+    fn default() -> Self {
+        OutOfWorldPolicy::Restricted
+    }
+}
+
 #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Page<Coord> {
     pub bitmap: [[RootId; PAGE_WIDTH as usize]; PAGE_LENGTH as usize],
@@ -1326,6 +1575,7 @@ pub trait Trait: accounts::Trait {
     + IntDiv
     + FromRaw
     + CastToType
+    + FromInt
     + ToBigCoord<Output = Self::BigCoord>;
 
     // Required for global calculations, where Coord is not enough. Not for common usage.
@@ -1337,7 +1587,9 @@ pub trait Trait: accounts::Trait {
     + PartialOrd
     + Copy
     + FromBigCoord<Output = Self::Coord>
-    + GetEpsilon;
+    + GetEpsilon
+    + IntDiv
+    + FromInt;
     
     type RawCoord: Default 
     + Parameter 
@@ -1349,15 +1601,59 @@ pub trait Trait: accounts::Trait {
     
     /// Max available height of any building
     type MaxHeight: Get<LightCoord>;
-}    
+
+    /// Amount of data carried by zone events, see `EventVerbosity`
+    type EventVerbosity: Get<EventVerbosity>;
+
+    /// Max amount of zones on the whole map, bounds global state growth
+    type MaxTotalZones: Get<u32>;
+
+    /// Whether admins and root origin may add zones above MaxTotalZones
+    type AdminsExemptFromMaxTotalZones: Get<bool>;
+
+    /// Max length of attestation bytes, stored for a zone
+    type MaxAttestationLength: Get<u32>;
+
+    /// Max length of zone name
+    type MaxZoneNameLen: Get<u32>;
+
+    /// How points outside of all roots are treated by queries
+    type OutOfWorldPolicy: Get<OutOfWorldPolicy>;
+
+    /// If true, removed zones are kept in archive
+    type ArchiveRemovedZones: Get<bool>;
+
+    /// Amount of blocks, for which removed zones are kept in archive
+    type ArchiveRetention: Get<Self::BlockNumber>;
+
+    /// Clearance above red zone, which is restricted as well as the zone itself
+    type RestrictedAltitudeBuffer: Get<LightCoord>;
+
+    /// Max amount of zones, added by a single zone_add_batch call
+    type MaxZonesPerBatch: Get<u32>;
+
+    /// Amount of blocks after zone creation or removal in an area, during which
+    /// no zones can be created there. Zero disables the cooldown.
+    /// Zones, added by the same zone_add_batch call, don't block each other.
+    type RegionCooldownBlocks: Get<Self::BlockNumber>;
+
+    /// Max amount of temporary zones, which can expire at the same block
+    type MaxExpiriesPerBlock: Get<u32>;
+}
 
 pub trait WeightInfo {
     fn root_add() -> Weight;
-    fn zone_add() -> Weight;
+    fn zone_add(z: u32) -> Weight;
     fn root_remove() -> Weight;
     fn zone_remove() -> Weight;
     fn change_area_type() -> Weight;
     fn route_add() -> Weight;
+    fn set_coordinate_system() -> Weight;
+    fn zone_attest() -> Weight;
+    fn zone_set_frozen() -> Weight;
+    fn zone_add_batch(zones: u32, z: u32) -> Weight;
+    fn zone_soft_delete() -> Weight;
+    fn map_init() -> Weight;
 }
 
 decl_storage! {
@@ -1377,6 +1673,80 @@ decl_storage! {
 
         RedZones get(fn zone_data): 
             map hasher(blake2_128_concat) ZoneId => ZoneOf<T>;
+
+        /// Registrar, who added the zone
+        ZoneAuthor get(fn author_of):
+            map hasher(blake2_128_concat) ZoneId => Option<T::AccountId>;
+
+        /// Reverse index of ZoneAuthor
+        AuthorZones get(fn is_author_of):
+            double_map hasher(blake2_128_concat) T::AccountId,
+                       hasher(blake2_128_concat) ZoneId => bool;
+
+        MapCoordinateSystem get(fn coordinate_system): CoordinateSystem;
+
+        TotalZones get(fn total_zones): u32;
+
+        /// Bounding box of the whole map. Roots and zones can't be added outside of it, once it is set
+        MapBounds get(fn map_bounds): Option<Box3D<T::Coord>>;
+
+        /// Layout of stored data. New chains start with the latest one
+        StorageVersion get(fn storage_version) build(|_| Releases::V2): Releases;
+
+        /// Off-chain signed approvals of zones [signer, signature]
+        ZoneAttestations get(fn zone_attestation):
+            map hasher(blake2_128_concat) ZoneId => Option<(T::AccountId, Vec<u8>)>;
+
+        /// Zones, which enforcement is suspended. Frozen zones are still listed, but don't restrict anything
+        FrozenZones get(fn is_frozen):
+            map hasher(blake2_128_concat) ZoneId => bool;
+
+        /// Block, at which zone was created
+        ZoneCreatedAt get(fn created_at):
+            map hasher(blake2_128_concat) ZoneId => Option<T::BlockNumber>;
+
+        /// Reverse index of ZoneCreatedAt [creation block, zone number]
+        ZonesByCreationBlock:
+            double_map hasher(twox_64_concat) T::BlockNumber,
+                       hasher(blake2_128_concat) ZoneId => ();
+
+        /// Block, at which temporary zone is removed
+        ZoneExpiresAt get(fn expires_at):
+            map hasher(blake2_128_concat) ZoneId => Option<T::BlockNumber>;
+
+        /// Temporary zones, which should be removed at given block
+        ExpiryQueue get(fn expiry_queue):
+            map hasher(blake2_128_concat) T::BlockNumber => Vec<ZoneId>;
+
+        /// Soft deleted zones. They are kept in storage, but hidden from enforcement and spatial queries
+        DeletedZones get(fn is_deleted):
+            map hasher(blake2_128_concat) ZoneId => bool;
+
+        /// Removed zones, kept for retention window [zone number, removal block => zone, who].
+        /// Who is None for root origin and expiry. Reused id keeps records of each removal,
+        /// except removals within the same block, where the last one is kept.
+        ArchivedZones get(fn archived_zone):
+            double_map hasher(blake2_128_concat) ZoneId,
+                       hasher(blake2_128_concat) T::BlockNumber => Option<(ZoneOf<T>, Option<T::AccountId>)>;
+
+        /// Archived zones, which should be pruned at given block [zone number, removal block]
+        ArchiveExpiry get(fn archive_expiry):
+            map hasher(blake2_128_concat) T::BlockNumber => Vec<(ZoneId, T::BlockNumber)>;
+
+        /// Block of last zone creation or removal in area, used for RegionCooldownBlocks
+        AreaLastChange get(fn area_last_change):
+            double_map hasher(blake2_128_concat) RootId,
+                       hasher(blake2_128_concat) AreaId => Option<T::BlockNumber>;
+
+        /// Amount of zones in area, which are not soft deleted
+        AreaDensity get(fn area_density):
+            double_map hasher(blake2_128_concat) RootId,
+                       hasher(blake2_128_concat) AreaId => u32;
+
+        /// Areas, grouped by AreaDensity, so the densest one is found without a scan [density, (root, area)]
+        AreasByDensity:
+            double_map hasher(twox_64_concat) u32,
+                       hasher(blake2_128_concat) (RootId, AreaId) => ();
     }
 }
 
@@ -1394,18 +1764,40 @@ decl_event!(
         Coord = <T as Trait>::Coord,
     {
         // Event documentation should end with an array that provides descriptive names for event parameters.
-        /// New root box has been created [box number, who]
-        RootCreated(RootId, AccountId),
-        /// New zone added [root, area, zone number, who]
-        ZoneCreated(RootId, AreaId, ZoneId, AccountId),
-        /// Area type changed [role, area, root, who]
-        AreaTypeChanged(u8, AreaId, RootId, AccountId),
-        /// Root was removed from storage
-        RootRemoved(RootId, AccountId),
-        /// Zone was removed from storage
-        ZoneRemoved(ZoneId, AccountId),
+        /// New root box has been created, who is None for root origin [box number, who]
+        RootCreated(RootId, Option<AccountId>),
+        /// New zone added, who is None for root origin [root, area, zone number, who, rect, height]
+        ZoneCreated(RootId, AreaId, ZoneId, Option<AccountId>, Rect2D<Coord>, LightCoord),
+        /// New zone added, minimal verbosity [zone number]
+        ZoneCreatedMinimal(ZoneId),
+        /// New zone added, full verbosity [root, area, zone number, who, rect, height, name]
+        ZoneCreatedFull(RootId, AreaId, ZoneId, Option<AccountId>, Rect2D<Coord>, LightCoord, Vec<u8>),
+        /// Area type changed, who is None for root origin [role, area, root, who]
+        AreaTypeChanged(u8, AreaId, RootId, Option<AccountId>),
+        /// Root was removed from storage, who is None for root origin [root, who]
+        RootRemoved(RootId, Option<AccountId>),
+        /// Zone was removed from storage, who is None for root origin [zone number, who]
+        ZoneRemoved(ZoneId, Option<AccountId>),
+        /// Zone was removed from storage, minimal verbosity [zone number]
+        ZoneRemovedMinimal(ZoneId),
+        /// Zone was removed from storage, full verbosity [zone number, who, rect, height]
+        ZoneRemovedFull(ZoneId, Option<AccountId>, Rect2D<Coord>, LightCoord),
         /// New route was submitted [start, destination, start, arrival, rootId, who]
         RouteAdded(Point3D<Coord>, Point3D<Coord>, Moment, Moment, RootId, AccountId),
+        /// Coordinate system of the map was changed, who is None for root origin [coordinate system, who]
+        CoordinateSystemChanged(CoordinateSystem, Option<AccountId>),
+        /// Attestation was attached to the zone, who is None for root origin [zone number, signer, who]
+        ZoneAttested(ZoneId, AccountId, Option<AccountId>),
+        /// Zone enforcement was suspended or resumed [zone number, frozen]
+        ZoneFrozen(ZoneId, bool),
+        /// Zone was soft deleted, its record is kept [zone number, who]
+        ZoneSoftDeleted(ZoneId, AccountId),
+        /// Soft deleted zone was restored [zone number, who]
+        ZoneRestored(ZoneId, AccountId),
+        /// Temporary zone has been removed [zone number]
+        ZoneExpired(ZoneId),
+        /// Map bounds have been set [bounding box]
+        MapInitialized(Box3D<Coord>),
     }
 );
 
@@ -1449,6 +1841,16 @@ decl_error! {
         RouteDoesNotFitToRoot,
         /// Route intersect 1 or more zones
         RouteIntersectRedZone, 
+        /// Map already contains max amount of zones
+        MapFull,
+        /// Zones in area were changed recently, creation is blocked for RegionCooldownBlocks
+        RegionCoolingDown,
+        /// Zone lies outside of map bounds
+        OutsideMapBounds,
+        /// Too many zones expire at given block already
+        ExpiryQueueFull,
+        /// Weight hint is below amount of items, which call has to process
+        WeightHintTooLow,
         // Add additional errors below
     }
 }
@@ -1464,11 +1866,53 @@ decl_module! {
         // Events must be initialized if they are used by the pallet.
         fn deposit_event() = default;
 
+        /// Checks relations between pallet constants, so misconfigured runtime fails loudly
+        fn integrity_test() {
+            assert!(T::MaxBuildingsInArea::get() > 0, "MaxBuildingsInArea must allow at least one zone in area");
+            assert!(T::MaxHeight::get() > 0, "MaxHeight must be above the ground");
+            assert!(T::MaxTotalZones::get() > 0, "MaxTotalZones must allow at least one zone");
+            assert!(
+                !T::ArchiveRemovedZones::get() || T::ArchiveRetention::get() > Zero::zero(),
+                "ArchiveRetention must be positive, if archive is enabled"
+            );
+            assert!(T::MaxZonesPerBatch::get() > 0, "MaxZonesPerBatch must allow at least one zone");
+            assert!(T::MaxExpiriesPerBlock::get() > 0, "MaxExpiriesPerBlock must allow at least one zone");
+        }
+
+        /// Migrates stored data to the latest layout
+        fn on_runtime_upgrade() -> Weight {
+            migrations::migrate_to_v2::<T>()
+        }
+
+        /// Prunes archived zones, which retention window ends at this block,
+        /// and removes temporary zones, which expire at this block
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let expired = ArchiveExpiry::<T>::take(now);
+            let mut weight = T::DbWeight::get().reads_writes(1, 1 + expired.len() as Weight);
+            for (zone_id, removed_at) in expired.iter() {
+                ArchivedZones::<T>::remove(zone_id, removed_at);
+            }
+            // Amount of due zones is bounded by MaxExpiriesPerBlock
+            let due_zones = ExpiryQueue::<T>::take(now);
+            weight = weight.saturating_add(T::DbWeight::get().reads_writes(1 + due_zones.len() as Weight, 1));
+            for zone_id in due_zones.iter() {
+                // Zone might be removed earlier, and its id reused by another zone
+                if ZoneExpiresAt::<T>::get(zone_id) == Some(now) {
+                    Self::purge_zone(*zone_id, None);
+                    Self::deposit_event(RawEvent::ZoneExpired(*zone_id));
+                    weight = weight.saturating_add(Self::purge_zone_weight());
+                }
+            }
+            weight
+        }
+
         /// Adds new RootBox to storage
         #[weight = <T as Trait>::WeightInfo::root_add()]
         pub fn root_add(origin, bounding_box: Box3D<T::Coord>, delta: T::Coord) -> dispatch::DispatchResult {
-            let who = ensure_signed(origin)?;
-            ensure!(<accounts::Module<T>>::account_is(&who, REGISTRAR_ROLE.into()), Error::<T>::NotAuthorized);
+            let who = Self::ensure_registrar_or_root(origin)?;
+            if let Some(bounds) = MapBounds::<T>::get() {
+                ensure!(bounds.contains_box(bounding_box), Error::<T>::OutsideMapBounds);
+            }
 
             // Check amount of pages to be extracted
             let amount_of_pages_to_extract = Page::<T::Coord>::get_amount_of_pages_to_extract_using_box(bounding_box);
@@ -1534,6 +1978,7 @@ decl_module! {
                 updated_pages.push(page);
             }
 
+            Self::note_caller_operation(&who)?;
             for (page_number, page_index) in page_indexes.into_iter().enumerate() {
                 EarthBitmap::<T>::insert(page_index, updated_pages[page_number]);
             }
@@ -1551,8 +1996,7 @@ decl_module! {
                             // Coords is SW {lat, lon, alt} NE {lat, lon, alt} 
                             raw_box: [T::RawCoord; 6],
                             raw_delta: T::RawCoord) -> dispatch::DispatchResult {
-            let who = ensure_signed(origin.clone())?;
-            ensure!(<accounts::Module<T>>::account_is(&who, REGISTRAR_ROLE.into()), Error::<T>::NotAuthorized);
+            Self::ensure_registrar_or_root(origin.clone())?;
 
             let south_west = Point3D::new(T::Coord::from_raw(raw_box[0].into()), 
                                           T::Coord::from_raw(raw_box[1].into()), 
@@ -1567,69 +2011,77 @@ decl_module! {
         }
 
         /// Form index and store input to redzones, creates area struct if it doesnt exist
-        #[weight = <T as Trait>::WeightInfo::zone_add()]
+        #[weight = <T as Trait>::WeightInfo::zone_add(T::MaxBuildingsInArea::get() as u32)]
         pub fn zone_add(origin, 
                         rect: Rect2D<T::Coord>,
                         height: LightCoord,
                         root_id: RootId) -> dispatch::DispatchResult {
-            let who = ensure_signed(origin)?;
-            ensure!(<accounts::Module<T>>::account_is(&who, REGISTRAR_ROLE.into()), Error::<T>::NotAuthorized);
-            ensure!(RootBoxes::<T>::contains_key(root_id), Error::<T>::RootDoesNotExist);
-            ensure!(height < T::MaxHeight::get(), Error::<T>::InvalidData);
-            // Check if zone lies in one single area 
-            let area_id = RootBoxes::<T>::get(root_id).detect_intersected_area(rect.south_west);
-            let se_area_id = RootBoxes::<T>::get(root_id).detect_intersected_area(rect.north_east);
-            ensure!(area_id == se_area_id, Error::<T>::ZoneDoesntFit);
-            // Getting area from storage, or creating it
-            let (area, area_existed) = if AreaData::contains_key(root_id, area_id) {
-                (AreaData::get(root_id, area_id), true)
-            } else {
-                AreaData::insert(root_id, area_id, Area::new(GREEN_AREA));
-                (Area::new(GREEN_AREA), false)
-            };
+            let who = Self::ensure_registrar_or_root(origin)?;
+            Self::create_zone(who, rect, height, root_id, Vec::new())?;
+            Ok(())
+        }
 
-            let max_zones = T::MaxBuildingsInArea::get();
-            let first_empty_id = Self::pack_index(root_id, area_id, 0);
-            let mut zone_id = first_empty_id;
-            
-            // If area already exists, we check if it's full, and check all zones inside for intersection
-            if area_existed {
-                ensure!(area.area_type == GREEN_AREA, Error::<T>::ForbiddenArea); 
-                let mut current_zone = first_empty_id;
-                let mut empty_id_found = false;
-                // Maybe, this cycle should be splitted in two. One finds first unused Id,
-                // and only if it was found, we should look for intersections. Not sure.
-                while current_zone < first_empty_id + max_zones as ZoneId {
-                    if RedZones::<T>::contains_key(current_zone) || empty_id_found {
-                        // Check if our zone overlaps with another zone in current area
-                        let rect_to_check = RedZones::<T>::get(current_zone).rect;
-                        ensure!(!rect_to_check.intersects_rect(rect), Error::<T>::OverlappingZone);
-                        current_zone += 1;
-                    } else { 
-                        zone_id = current_zone;
-                        empty_id_found = true;
-                    }
-                } 
-                ensure!(empty_id_found, Error::<T>::AreaFull);
-            } else {
-                // This is first zone in area, we don't need to check any intersections and stuff.
-                zone_id = first_empty_id; 
+        /// Same as zone_add, but also labels the zone with a human readable name
+        #[weight = <T as Trait>::WeightInfo::zone_add(T::MaxBuildingsInArea::get() as u32)]
+        pub fn zone_add_named(origin,
+                              rect: Rect2D<T::Coord>,
+                              height: LightCoord,
+                              root_id: RootId,
+                              name: Vec<u8>) -> dispatch::DispatchResult {
+            let who = Self::ensure_registrar_or_root(origin)?;
+            Self::create_zone(who, rect, height, root_id, name)?;
+            Ok(())
+        }
+
+        /// Same as zone_add, but zone is removed automatically at `expires_at` block
+        #[weight = <T as Trait>::WeightInfo::zone_add(T::MaxBuildingsInArea::get() as u32)]
+        pub fn zone_add_temporary(origin,
+                                  rect: Rect2D<T::Coord>,
+                                  height: LightCoord,
+                                  root_id: RootId,
+                                  expires_at: T::BlockNumber) -> dispatch::DispatchResult {
+            let who = Self::ensure_registrar_or_root(origin)?;
+            ensure!(expires_at > <frame_system::Module<T>>::block_number(), Error::<T>::WrongTimeSupplied);
+            ensure!(
+                ExpiryQueue::<T>::decode_len(expires_at).unwrap_or(0) < T::MaxExpiriesPerBlock::get() as usize,
+                Error::<T>::ExpiryQueueFull
+            );
+            let zone_id = Self::create_zone(who, rect, height, root_id, Vec::new())?;
+            ZoneExpiresAt::<T>::insert(zone_id, expires_at);
+            ExpiryQueue::<T>::append(expires_at, zone_id);
+            Ok(())
+        }
+
+        /// Adds several zones to a root at once, e.g. for initial map import.
+        /// Either all zones are added, or none of them.
+        #[weight = <T as Trait>::WeightInfo::zone_add_batch(zones.len() as u32, T::MaxBuildingsInArea::get() as u32)]
+        #[transactional]
+        pub fn zone_add_batch(origin,
+                              zones: Vec<(Rect2D<T::Coord>, LightCoord)>,
+                              root_id: RootId) -> dispatch::DispatchResult {
+            let who = Self::ensure_registrar_or_root(origin)?;
+            ensure!(zones.len() as u32 <= T::MaxZonesPerBatch::get(), Error::<T>::InvalidData);
+            let mut touched_areas = BTreeSet::new();
+            for (rect, height) in zones.into_iter() {
+                // Every zone is counted, so batching doesn't bypass the daily limit
+                Self::note_caller_operation(&who)?;
+                let (area_id, zone_id) = Self::find_zone_slot(&who, rect, height, root_id)?;
+                // Zones, added earlier by this batch, don't hold the cooldown of their area
+                if touched_areas.insert(area_id) {
+                    Self::ensure_area_settled(root_id, area_id)?;
+                }
+                Self::store_zone(who.clone(), root_id, area_id, zone_id, rect, height, Vec::new());
             }
-            
-            let zone = ZoneOf::<T>::new(zone_id, rect, height);
-            RedZones::<T>::insert(zone_id, zone);
-            Self::deposit_event(RawEvent::ZoneCreated(root_id, area_id, zone_id, who));
             Ok(())
         }
 
         /// TODO fix this trouble with types, RawCoord is a one big crutch
-        #[weight = <T as Trait>::WeightInfo::zone_add()]
+        #[weight = <T as Trait>::WeightInfo::zone_add(T::MaxBuildingsInArea::get() as u32)]
         pub fn raw_zone_add(origin, 
                             raw_rect: [T::RawCoord; 4],
                             height: LightCoord,
                             root_id: RootId) -> dispatch::DispatchResult {
-            let who = ensure_signed(origin.clone())?;
-            ensure!(<accounts::Module<T>>::account_is(&who, REGISTRAR_ROLE.into()), Error::<T>::NotAuthorized);
+            Self::ensure_registrar_or_root(origin.clone())?;
 
             let south_west = Point2D::new(T::Coord::from_raw(raw_rect[0].into()), 
                                           T::Coord::from_raw(raw_rect[1].into()));
@@ -1641,27 +2093,22 @@ decl_module! {
         }
 
         /// Removes root by given id, and zones inside. This means, function might be heavy.
-        #[weight = <T as Trait>::WeightInfo::root_remove()]
-        pub fn root_remove(origin, root_id: RootId) -> dispatch::DispatchResult {
-            let who = ensure_signed(origin)?;
-            ensure!(<accounts::Module<T>>::account_is(&who, REGISTRAR_ROLE.into()), Error::<T>::NotAuthorized);
+        /// `zones_hint` is the max amount of zones in the root, weight is charged for it.
+        /// Fails, if the root holds more zones.
+        #[weight = <T as Trait>::WeightInfo::root_remove()
+            .saturating_add(Module::<T>::purge_zone_weight().saturating_mul(*zones_hint as Weight))]
+        pub fn root_remove(origin, root_id: RootId, zones_hint: u32) -> dispatch::DispatchResult {
+            let who = Self::ensure_registrar_or_root(origin)?;
             ensure!(RootBoxes::<T>::contains_key(root_id), Error::<T>::RootDoesNotExist);
-
-            let max_zones = T::MaxBuildingsInArea::get();
-            let max_areas = RootBoxes::<T>::get(root_id).get_max_area();
-            // Recursively remove all zones inside selected root
-            let mut zone_id = Self::pack_index(root_id, 0, 0);
-            let mut area_id = 0;
-            while area_id <= max_areas {
-                let max_zones_in_area = zone_id + max_zones as ZoneId;
-                while zone_id < max_zones_in_area {
-                    if RedZones::<T>::contains_key(zone_id) {
-                        RedZones::<T>::remove(zone_id); 
-                    }
-                    zone_id += 1;
-                }
-                area_id += 1;
-                zone_id = Self::pack_index(root_id, area_id, 0);
+            // Zones are stored only in areas, which have AreaData
+            let zones: Vec<ZoneId> = AreaData::iter_prefix(root_id)
+                .flat_map(|(area_id, _)| Self::zones_in_area(root_id, area_id))
+                .collect();
+            ensure!(zones.len() <= zones_hint as usize, Error::<T>::WeightHintTooLow);
+            Self::note_caller_operation(&who)?;
+
+            for zone_id in zones {
+                Self::purge_zone(zone_id, who.clone());
             }
 
             // Recursively clear all cells in bitmap
@@ -1727,19 +2174,22 @@ decl_module! {
             }
 
             RootBoxes::<T>::remove(root_id);
+            AreaLastChange::<T>::remove_prefix(root_id);
             Self::deposit_event(RawEvent::RootRemoved(root_id, who));
             Ok(())
         }
 
         /// Removes zone by given id
-        #[weight = <T as Trait>::WeightInfo::zone_remove()]
+        #[weight = <T as Trait>::WeightInfo::zone_remove().saturating_add(Module::<T>::purge_zone_weight())]
         pub fn zone_remove(origin, zone_id: ZoneId) -> dispatch::DispatchResult {
-            let who = ensure_signed(origin)?;
-            ensure!(<accounts::Module<T>>::account_is(&who, REGISTRAR_ROLE.into()), Error::<T>::NotAuthorized);
+            let who = Self::ensure_registrar_or_root(origin)?;
             ensure!(RedZones::<T>::contains_key(zone_id), Error::<T>::ZoneDoesntExist);
+            // Only registrar, who added the zone, or root can remove it
+            ensure!(who.is_none() || Self::author_of(zone_id) == who, Error::<T>::NotAuthorized);
+            Self::note_caller_operation(&who)?;
             
-            RedZones::<T>::remove(zone_id);
-            Self::deposit_event(RawEvent::ZoneRemoved(zone_id, who));
+            let zone = Self::purge_zone(zone_id, who.clone());
+            Self::deposit_zone_removed(zone_id, who, zone);
             Ok(())
         }
         
@@ -1749,9 +2199,9 @@ decl_module! {
                                 root_id: RootId, 
                                 area_id: AreaId, 
                                 area_type: u8) -> dispatch::DispatchResult {
-            let who = ensure_signed(origin)?;
-            ensure!(<accounts::Module<T>>::account_is(&who, REGISTRAR_ROLE.into()), Error::<T>::NotAuthorized);
+            let who = Self::ensure_registrar_or_root(origin)?;
             ensure!(AreaData::contains_key(root_id, area_id), Error::<T>::NotExists);
+            Self::note_caller_operation(&who)?;
             
             AreaData::mutate(root_id, area_id, |ar| {
                 ar.area_type = area_type;
@@ -1795,11 +2245,13 @@ decl_module! {
                     // Loop through zones, maybe add constraint to MaxBuildingsInArea
                     while RedZones::<T>::contains_key(zone_id) {
                         // TODO ask about ensure!() usage in cycle
-                        ensure!(!route_line.intersects_rect(RedZones::<T>::get(zone_id).rect), Error::<T>::RouteIntersectRedZone);
+                        ensure!(!Self::is_enforced(zone_id) || !route_line.intersects_rect(RedZones::<T>::get(zone_id).rect), 
+                                Error::<T>::RouteIntersectRedZone);
                         zone_id += 1;
                     }
                 }
             }
+            <accounts::Module<T>>::note_operation(&who)?;
             Self::deposit_event(RawEvent::RouteAdded(
                 start_waypoint.location, end_waypoint.location, 
                 start_time, arrival_time, root_id, who
@@ -1828,6 +2280,92 @@ decl_module! {
             let arrival_waypoint = Waypoint::new(arrival_location, arrival_time);
             Module::<T>::route_add(origin, vec![start_waypoint, arrival_waypoint], root_id)
         }
+
+        /// Sets bounding box of the whole map, or replaces already set one.
+        /// Every existing root should fit into new bounds. Zones lie inside their roots horizontally,
+        /// and their heights aren't rechecked.
+        #[weight = <T as Trait>::WeightInfo::map_init()]
+        pub fn map_init(origin, global_bounds: Box3D<T::Coord>) -> dispatch::DispatchResult {
+            ensure_root(origin)?;
+            ensure!(Self::is_valid_box(&global_bounds), Error::<T>::InvalidCoords);
+            ensure!(
+                RootBoxes::<T>::iter_values().all(|root| global_bounds.contains_box(root.bounding_box)),
+                Error::<T>::OutsideMapBounds
+            );
+
+            MapBounds::<T>::put(global_bounds);
+            Self::deposit_event(RawEvent::MapInitialized(global_bounds));
+            Ok(())
+        }
+
+        /// Sets coordinate system, in which map coordinates should be interpreted
+        #[weight = <T as Trait>::WeightInfo::set_coordinate_system()]
+        pub fn set_coordinate_system(origin, coordinate_system: CoordinateSystem) -> dispatch::DispatchResult {
+            let who = Self::ensure_admin_or_root(origin)?;
+            Self::note_caller_operation(&who)?;
+
+            MapCoordinateSystem::put(coordinate_system);
+            Self::deposit_event(RawEvent::CoordinateSystemChanged(coordinate_system, who));
+            Ok(())
+        }
+
+        /// Stores attestation of a zone, signed off-chain by `signer`.
+        /// Signature is not verified on-chain, it is only stored and exposed.
+        #[weight = <T as Trait>::WeightInfo::zone_attest()]
+        pub fn zone_attest(origin, zone_id: ZoneId, signer: T::AccountId, signature: Vec<u8>) -> dispatch::DispatchResult {
+            let who = Self::ensure_registrar_or_root(origin)?;
+            ensure!(RedZones::<T>::contains_key(zone_id), Error::<T>::ZoneDoesntExist);
+            ensure!(signature.len() <= T::MaxAttestationLength::get() as usize, Error::<T>::InvalidData);
+            Self::note_caller_operation(&who)?;
+
+            ZoneAttestations::<T>::insert(zone_id, (signer.clone(), signature));
+            Self::deposit_event(RawEvent::ZoneAttested(zone_id, signer, who));
+            Ok(())
+        }
+
+        /// Suspends or resumes enforcement of a zone, keeping it in storage.
+        /// Only zone author or an admin can do this.
+        #[weight = <T as Trait>::WeightInfo::zone_set_frozen()]
+        pub fn zone_set_frozen(origin, zone_id: ZoneId, frozen: bool) -> dispatch::DispatchResult {
+            let who = Self::ensure_zone_manager(origin, zone_id)?;
+            <accounts::Module<T>>::note_operation(&who)?;
+
+            if frozen {
+                FrozenZones::insert(zone_id, true);
+            } else {
+                FrozenZones::remove(zone_id);
+            }
+            Self::deposit_event(RawEvent::ZoneFrozen(zone_id, frozen));
+            Ok(())
+        }
+
+        /// Hides zone from enforcement and spatial queries, keeping its record for zone_restore
+        #[weight = <T as Trait>::WeightInfo::zone_soft_delete()]
+        pub fn zone_soft_delete(origin, zone_id: ZoneId) -> dispatch::DispatchResult {
+            let who = Self::ensure_zone_manager(origin, zone_id)?;
+            ensure!(!DeletedZones::get(zone_id), Error::<T>::InvalidAction);
+            <accounts::Module<T>>::note_operation(&who)?;
+
+            DeletedZones::insert(zone_id, true);
+            let (root_id, area_id, _) = Self::unpack_index(zone_id);
+            Self::shift_density(root_id, area_id, false);
+            Self::deposit_event(RawEvent::ZoneSoftDeleted(zone_id, who));
+            Ok(())
+        }
+
+        /// Brings soft deleted zone back into enforcement
+        #[weight = <T as Trait>::WeightInfo::zone_soft_delete()]
+        pub fn zone_restore(origin, zone_id: ZoneId) -> dispatch::DispatchResult {
+            let who = Self::ensure_zone_manager(origin, zone_id)?;
+            ensure!(DeletedZones::get(zone_id), Error::<T>::InvalidAction);
+            <accounts::Module<T>>::note_operation(&who)?;
+
+            DeletedZones::remove(zone_id);
+            let (root_id, area_id, _) = Self::unpack_index(zone_id);
+            Self::shift_density(root_id, area_id, true);
+            Self::deposit_event(RawEvent::ZoneRestored(zone_id, who));
+            Ok(())
+        }
     }
 }
 
@@ -1852,6 +2390,50 @@ impl<T: Trait> Module<T> {
         let alt = T::Coord::from_raw(0);
 
         let point = Point3D::<T::Coord>::new(lat, lon, alt);
+        Self::bitmap_root_at(point)
+    }
+
+    /// Ensures origin is either root, or signed by registrar. Returns signer account,
+    /// or None for root origin.
+    pub fn ensure_registrar_or_root(origin: T::Origin) -> Result<Option<T::AccountId>, dispatch::DispatchError> {
+        if ensure_root(origin.clone()).is_ok() {
+            return Ok(None);
+        }
+        let who = ensure_signed(origin)?;
+        ensure!(<accounts::Module<T>>::account_is(&who, REGISTRAR_ROLE.into()), Error::<T>::NotAuthorized);
+        Ok(Some(who))
+    }
+
+    /// Ensures origin is either root, or signed by admin. Returns signer account,
+    /// or None for root origin.
+    fn ensure_admin_or_root(origin: T::Origin) -> Result<Option<T::AccountId>, dispatch::DispatchError> {
+        if ensure_root(origin.clone()).is_ok() {
+            return Ok(None);
+        }
+        let who = ensure_signed(origin)?;
+        ensure!(<accounts::Module<T>>::account_is(&who, ADMIN_ROLE.into()), Error::<T>::NotAuthorized);
+        Ok(Some(who))
+    }
+
+    /// Counts mutating call of a signed caller. Root origin is not rate limited.
+    fn note_caller_operation(who: &Option<T::AccountId>) -> dispatch::DispatchResult {
+        match who {
+            Some(who) => <accounts::Module<T>>::note_operation(who),
+            None => Ok(()),
+        }
+    }
+
+    /// Ensures origin is signed by the zone author, or by an admin. Returns signer account
+    fn ensure_zone_manager(origin: T::Origin, zone_id: ZoneId) -> Result<T::AccountId, dispatch::DispatchError> {
+        let who = ensure_signed(origin)?;
+        ensure!(RedZones::<T>::contains_key(zone_id), Error::<T>::ZoneDoesntExist);
+        ensure!(Self::author_of(zone_id) == Some(who.clone()) || <accounts::Module<T>>::account_is(&who, ADMIN_ROLE.into()),
+                Error::<T>::NotAuthorized);
+        Ok(who)
+    }
+
+    /// Reads id of the root, which owns bitmap cell with given point
+    fn bitmap_root_at(point: Point3D<T::Coord>) -> RootId {
         let (row, column) = Page::<T::Coord>::get_cell_indexes(point);
         let index = Page::<T::Coord>::get_index(row, column);
         let bitmap = EarthBitmap::<T>::get(index).bitmap;
@@ -1859,6 +2441,357 @@ impl<T: Trait> Module<T> {
         bitmap[(row % PAGE_LENGTH) as usize][(column % PAGE_WIDTH) as usize]
     }
 
+    /// Finds root and area, in which given point is located
+    pub fn locate(point: Point3D<T::Coord>) -> Option<(RootId, AreaId)> {
+        let zero = T::Coord::default();
+        // Bitmap doesn't cover negative coordinates
+        if point.lat < zero || point.lon < zero {
+            return None;
+        }
+        let root_id = Self::bitmap_root_at(point);
+        if root_id == 0 || !RootBoxes::<T>::contains_key(root_id) {
+            return None;
+        }
+        match RootBoxes::<T>::get(root_id).detect_intersected_area(point.project()) {
+            0 => None,
+            area_id => Some((root_id, area_id)),
+        }
+    }
+
+    /// True, if zone restricts airspace, i.e. it is neither frozen, nor soft deleted
+    fn is_enforced(zone_id: ZoneId) -> bool {
+        !FrozenZones::get(zone_id) && !DeletedZones::get(zone_id)
+    }
+
+    /// Returns ids of zones in the area, which are visible to spatial queries, i.e. not soft deleted
+    fn visible_zones_in_area(root_id: RootId, area_id: AreaId) -> Vec<ZoneId> {
+        Self::zones_in_area(root_id, area_id)
+            .into_iter()
+            .filter(|zone_id| !DeletedZones::get(zone_id))
+            .collect()
+    }
+
+    /// Returns ids of zones in the area, which are enforced, i.e. not frozen or soft deleted
+    fn enforced_zones_in_area(root_id: RootId, area_id: AreaId) -> Vec<ZoneId> {
+        Self::zones_in_area(root_id, area_id)
+            .into_iter()
+            .filter(|zone_id| Self::is_enforced(*zone_id))
+            .collect()
+    }
+
+    /// True, if restricted airspace of the zone contains given point. Same containment, as in zone_at:
+    /// a point on south, west or ground face is inside, a point on north, east or top face is not.
+    fn zone_contains(zone: &ZoneOf<T>, point: Point3D<T::Coord>) -> bool {
+        Self::restricted_box(zone).contains_point(point)
+    }
+
+    /// Zone box, raised up to restricted ceiling
+    fn restricted_box(zone: &ZoneOf<T>) -> Box3D<T::Coord> {
+        let mut restricted = Self::zone_box(zone);
+        restricted.north_east.alt = T::Coord::from_int(Self::restricted_ceiling(zone));
+        restricted
+    }
+
+    /// Height, below which airspace over the zone is restricted, including altitude buffer
+    fn restricted_ceiling(zone: &ZoneOf<T>) -> LightCoord {
+        zone.height.saturating_add(T::RestrictedAltitudeBuffer::get())
+    }
+
+    /// True, if point lies inside any red zone, or inside an area, which is not green.
+    /// Points outside of all roots are treated according to OutOfWorldPolicy.
+    pub fn is_restricted(point: Point3D<T::Coord>) -> bool {
+        let (root_id, area_id) = match Self::locate(point) {
+            Some(location) => location,
+            None => return T::OutOfWorldPolicy::get() == OutOfWorldPolicy::Restricted,
+        };
+        if AreaData::contains_key(root_id, area_id) && AreaData::get(root_id, area_id).area_type != GREEN_AREA {
+            return true;
+        }
+        Self::enforced_zones_in_area(root_id, area_id)
+            .into_iter()
+            .any(|zone_id| Self::zone_contains(&RedZones::<T>::get(zone_id), point))
+    }
+
+    /// Same as is_restricted, but for many points at once. Zones of each area are read only once.
+    /// Output matches input index for index. None, if there are more than MAX_POINTS_PER_QUERY points.
+    pub fn restriction_bitmap(points: &[Point3D<T::Coord>]) -> Option<Vec<bool>> {
+        if points.len() > MAX_POINTS_PER_QUERY {
+            return None;
+        }
+        let mut candidates: BTreeMap<(RootId, AreaId), Option<Vec<ZoneOf<T>>>> = BTreeMap::new();
+        let bitmap = points.iter().map(|point| {
+            let (root_id, area_id) = match Self::locate(*point) {
+                Some(location) => location,
+                None => return T::OutOfWorldPolicy::get() == OutOfWorldPolicy::Restricted,
+            };
+            // None stands for a forbidden area, where every point is restricted
+            let zones = candidates.entry((root_id, area_id)).or_insert_with(|| {
+                if AreaData::contains_key(root_id, area_id) && AreaData::get(root_id, area_id).area_type != GREEN_AREA {
+                    return None;
+                }
+                Some(Self::enforced_zones_in_area(root_id, area_id)
+                    .into_iter()
+                    .map(RedZones::<T>::get)
+                    .collect())
+            });
+            match zones {
+                Some(zones) => zones.iter().any(|zone| Self::zone_contains(zone, *point)),
+                None => true,
+            }
+        }).collect();
+        Some(bitmap)
+    }
+
+    /// Returns zones, crossed by segment from given point along heading.
+    /// Segment ends at `from + heading * max_distance`, so heading is displacement per unit of distance.
+    /// Zones behind the start point, or further than max_distance, are not included.
+    /// Empty, if max_distance is negative or above MAX_PROJECTION_SCALE, or the end point overflows Coord.
+    pub fn reachable_zones(from: Point3D<T::Coord>, heading: Point2D<T::Coord>, max_distance: T::Coord) -> Vec<ZoneId> {
+        let root_id = Self::bitmap_root_at(from);
+        if root_id == 0 || !RootBoxes::<T>::contains_key(root_id) {
+            return Vec::new();
+        }
+        let start = from.project();
+        let end = match Self::project_segment_end(start, heading, max_distance) {
+            Some(end) => end,
+            None => return Vec::new(),
+        };
+        let line = Line::new(start, end);
+
+        Self::zones_along_segment(root_id, start, end)
+            .into_iter()
+            .filter(|zone_id| Self::is_enforced(*zone_id))
+            .filter(|zone_id| {
+                let rect = RedZones::<T>::get(zone_id).rect;
+                line.intersects_rect(rect) || rect.is_point_inside(end)
+            })
+            .collect()
+    }
+
+    /// Predicts zones, which drone will enter within horizon, moving with constant velocity.
+    /// Returns zone ids with time left till entry, sorted by proximity.
+    /// Zones, which already contain the position, are reported with zero time.
+    /// Empty, if horizon is negative or above MAX_PROJECTION_SCALE, or the end point overflows Coord.
+    pub fn predict_entries(pos: Point3D<T::Coord>, velocity: Point2D<T::Coord>, horizon: T::Coord) -> Vec<(ZoneId, T::Coord)> {
+        let root_id = Self::bitmap_root_at(pos);
+        if root_id == 0 || !RootBoxes::<T>::contains_key(root_id) {
+            return Vec::new();
+        }
+        let start = pos.project();
+        let end = match Self::project_segment_end(start, velocity, horizon) {
+            Some(end) => end,
+            None => return Vec::new(),
+        };
+
+        let mut output: Vec<(ZoneId, T::Coord)> = Self::zones_along_segment(root_id, start, end)
+            .into_iter()
+            .filter(|zone_id| Self::is_enforced(*zone_id))
+            .filter_map(|zone_id| {
+                let zone = RedZones::<T>::get(zone_id);
+                if pos.alt >= T::Coord::from_int(Self::restricted_ceiling(&zone)) {
+                    return None;
+                }
+                Self::crossing_interval(zone.rect, start, velocity, horizon).map(|(enter, _)| (zone_id, enter.try_from()))
+            })
+            .collect();
+        output.sort_by(|a, b| a.1.cmp(&b.1));
+        output
+    }
+
+    /// Part of the segment between two points, which lies inside enforced red zones.
+    /// Segment is flat, at the altitude of `from`. Overlapping zones are counted once.
+    /// Segment, which can't be checked, as described in check_segment, is treated as restricted.
+    pub fn segment_restricted_fraction(from: Point3D<T::Coord>, to: Point3D<T::Coord>) -> Permill {
+        let zones = match Self::zones_crossed_by_segment(from, to) {
+            Some(zones) => zones,
+            None => return Permill::one(),
+        };
+        let start = from.project();
+        let end = to.project();
+        let direction = Point2D::new(end.lat - start.lat, end.lon - start.lon);
+        let whole = T::Coord::from_int(1);
+
+        let mut intervals: Vec<(T::BigCoord, T::BigCoord)> = zones
+            .into_iter()
+            .filter(|zone_id| Self::is_enforced(*zone_id))
+            .map(|zone_id| RedZones::<T>::get(zone_id))
+            .filter(|zone| from.alt < T::Coord::from_int(Self::restricted_ceiling(zone)))
+            .filter_map(|zone| Self::crossing_interval(zone.rect, start, direction, whole))
+            .collect();
+        intervals.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+        // Merge overlapping intervals, summing their lengths
+        let mut restricted = T::BigCoord::default();
+        let mut current: Option<(T::BigCoord, T::BigCoord)> = None;
+        for (enter, exit) in intervals {
+            current = match current {
+                Some((current_enter, current_exit)) if enter <= current_exit => {
+                    Some((current_enter, if exit > current_exit { exit } else { current_exit }))
+                },
+                Some((current_enter, current_exit)) => {
+                    restricted = restricted + (current_exit - current_enter);
+                    Some((enter, exit))
+                },
+                None => Some((enter, exit)),
+            };
+        }
+        if let Some((current_enter, current_exit)) = current {
+            restricted = restricted + (current_exit - current_enter);
+        }
+        let parts = (restricted * T::BigCoord::from_int(1_000_000)).integer_division_u32(T::BigCoord::from_int(1));
+        Permill::from_parts(min(parts, 1_000_000))
+    }
+
+    /// Returns enforced zones, which straight segment between `from` and `to` passes through,
+    /// including clearance above them. Zones of every root under the segment are checked.
+    /// None, if the segment can't be checked: either end is outside of all roots,
+    /// or the segment covers more than MAX_CELLS_PER_QUERY bitmap cells.
+    /// Dry run for flights: nothing is stored, touching zone faces is not a crossing.
+    pub fn check_segment(from: Point3D<T::Coord>, to: Point3D<T::Coord>) -> Option<Vec<ZoneId>> {
+        let zones = Self::zones_crossed_by_segment(from, to)?
+            .into_iter()
+            .filter(|zone_id| Self::is_enforced(*zone_id))
+            .filter(|zone_id| {
+                let zone = RedZones::<T>::get(zone_id);
+                Self::segment_crosses_box(Self::restricted_box(&zone), from, to)
+            })
+            .collect();
+        Some(zones)
+    }
+
+    /// True, if segment passes through box interior.
+    /// Slab method: intersect parameter intervals, while segment is inside box bounds on each axis.
+    fn segment_crosses_box(target: Box3D<T::Coord>, from: Point3D<T::Coord>, to: Point3D<T::Coord>) -> bool {
+        let zero = T::Coord::default();
+        let mut enter = T::BigCoord::default();
+        let mut exit = T::BigCoord::from_int(1);
+        let axes = [
+            (from.lat, to.lat - from.lat, target.south_west.lat, target.north_east.lat),
+            (from.lon, to.lon - from.lon, target.south_west.lon, target.north_east.lon),
+            (from.alt, to.alt - from.alt, target.south_west.alt, target.north_east.alt),
+        ];
+        for (position, delta, low, high) in axes.iter() {
+            if *delta == zero {
+                if position <= low || position >= high {
+                    return false;
+                }
+                continue;
+            }
+            let mut near = (*low - *position).try_into() / delta.try_into();
+            let mut far = (*high - *position).try_into() / delta.try_into();
+            if near > far {
+                swap(&mut near, &mut far);
+            }
+            if near > enter { enter = near; }
+            if far < exit { exit = far; }
+        }
+        enter < exit
+    }
+
+    /// Time interval, during which point moving with velocity is inside rect, clipped by horizon.
+    /// Slab method: intersect time intervals, while point is inside rect bounds on each axis.
+    fn crossing_interval(rect: Rect2D<T::Coord>, start: Point2D<T::Coord>, velocity: Point2D<T::Coord>,
+                         horizon: T::Coord) -> Option<(T::BigCoord, T::BigCoord)> {
+        let zero = T::Coord::default();
+        let mut enter = T::BigCoord::default();
+        let mut exit = horizon.try_into();
+        let axes = [
+            (start.lat, velocity.lat, rect.south_west.lat, rect.north_east.lat),
+            (start.lon, velocity.lon, rect.south_west.lon, rect.north_east.lon),
+        ];
+        for (position, speed, low, high) in axes.iter() {
+            if *speed == zero {
+                // Edges are excluded, same as in is_point_inside
+                if position <= low || position >= high {
+                    return None;
+                }
+                continue;
+            }
+            let mut near = (*low - *position).try_into() / speed.try_into();
+            let mut far = (*high - *position).try_into() / speed.try_into();
+            if near > far {
+                swap(&mut near, &mut far);
+            }
+            if near > enter { enter = near; }
+            if far < exit { exit = far; }
+        }
+        if enter < exit { Some((enter, exit)) } else { None }
+    }
+
+    /// Returns `start + direction * scale`, counted in BigCoord.
+    /// None, if scale is negative or above MAX_PROJECTION_SCALE, or the result doesn't fit into Coord.
+    fn project_segment_end(start: Point2D<T::Coord>, direction: Point2D<T::Coord>,
+                           scale: T::Coord) -> Option<Point2D<T::Coord>> {
+        if scale < T::Coord::default() || scale > T::Coord::from_int(MAX_PROJECTION_SCALE) {
+            return None;
+        }
+        let scale = scale.try_into();
+        let lat = (start.lat.try_into() + direction.lat.try_into() * scale).checked_from()?;
+        let lon = (start.lon.try_into() + direction.lon.try_into() * scale).checked_from()?;
+        Some(Point2D::new(lat, lon))
+    }
+
+    /// Returns zones of all roots and areas, which segment passes through.
+    /// None, if either end is outside of all roots, or the segment covers too many bitmap cells.
+    fn zones_crossed_by_segment(from: Point3D<T::Coord>, to: Point3D<T::Coord>) -> Option<Vec<ZoneId>> {
+        let mut output = Vec::new();
+        for root_id in Self::roots_along_segment(from, to)? {
+            output.extend(Self::zones_along_segment(root_id, from.project(), to.project()));
+        }
+        Some(output)
+    }
+
+    /// Returns roots, which own bitmap cells under the segment. Every cell of the segment's
+    /// bounding rectangle is read, so a root, clipped by the segment corner, isn't missed.
+    fn roots_along_segment(from: Point3D<T::Coord>, to: Point3D<T::Coord>) -> Option<Vec<RootId>> {
+        Self::locate(from)?;
+        Self::locate(to)?;
+        Self::roots_in_cells(from, to)
+    }
+
+    /// Returns roots, which own bitmap cells of the rectangle between two corner points.
+    /// None, if the rectangle covers more than MAX_CELLS_PER_QUERY cells.
+    fn roots_in_cells(a: Point3D<T::Coord>, b: Point3D<T::Coord>) -> Option<Vec<RootId>> {
+        let (a_row, a_column) = Page::<T::Coord>::get_cell_indexes(a);
+        let (b_row, b_column) = Page::<T::Coord>::get_cell_indexes(b);
+        let (min_row, max_row) = (min(a_row, b_row), max(a_row, b_row));
+        let (min_column, max_column) = (min(a_column, b_column), max(a_column, b_column));
+        let cells = (max_row - min_row + 1).saturating_mul(max_column - min_column + 1);
+        if cells > MAX_CELLS_PER_QUERY {
+            return None;
+        }
+
+        let mut pages: BTreeMap<PageId, PageOf<T>> = BTreeMap::new();
+        let mut roots = BTreeSet::new();
+        for row in min_row..=max_row {
+            for column in min_column..=max_column {
+                let index = Page::<T::Coord>::get_index(row, column);
+                let page = pages.entry(index).or_insert_with(|| EarthBitmap::<T>::get(index));
+                let root_id = page.bitmap[(row % PAGE_LENGTH) as usize][(column % PAGE_WIDTH) as usize];
+                if root_id != 0 && RootBoxes::<T>::contains_key(root_id) {
+                    roots.insert(root_id);
+                }
+            }
+        }
+        Some(roots.into_iter().collect())
+    }
+
+    /// Returns zones from all areas of given root, which segment passes through
+    fn zones_along_segment(root_id: RootId, start: Point2D<T::Coord>, end: Point2D<T::Coord>) -> Vec<ZoneId> {
+        let root = RootBoxes::<T>::get(root_id);
+        let mut route_areas = Line::new(start, end).get_route_areas(root);
+        route_areas.dedup();
+        let mut output = Vec::new();
+        for area_id in route_areas.into_iter().filter(|area_id| *area_id != 0) {
+            for zone_id in Self::zones_in_area(root_id, area_id) {
+                if !output.contains(&zone_id) {
+                    output.push(zone_id);
+                }
+            }
+        }
+        output
+    }
+
     /// Form index for storing zones, wrapped in u128............limited by const in runtime
     /// v................root id here..............v v.....area id.....v v..child objects..v
     /// 0000 0000 0000 0000 .... 0000 0000 0000 0000 0000 0000 0000 0000 0000 0000 0000 0000
@@ -1868,6 +2801,755 @@ impl<T: Trait> Module<T> {
         children as ZoneId
     }
 
+    /// Pre-flight check for zone creation. Returns the first reason, which blocks
+    /// `zone_add` with the same arguments, without mutating the state.
+    pub fn can_create(who: &T::AccountId,
+                      rect: Rect2D<T::Coord>,
+                      height: LightCoord,
+                      root_id: RootId) -> Result<(), Error<T>> {
+        ensure!(<accounts::Module<T>>::account_is(who, REGISTRAR_ROLE.into()), Error::<T>::NotAuthorized);
+        let (area_id, _) = Self::find_zone_slot(&Some(who.clone()), rect, height, root_id)?;
+        Self::ensure_area_settled(root_id, area_id)
+    }
+
+    /// Creates zone on behalf of already authorized `who` and returns its id.
+    /// `who` is None for root origin, such zones have no author.
+    /// This is what zone_add does after the origin check, so other pallets can call it
+    /// to capture assigned id directly. Id could be predicted with next_allocatable_id,
+    /// and is also carried by ZoneCreated events.
+    pub fn create_zone(who: Option<T::AccountId>,
+                       rect: Rect2D<T::Coord>,
+                       height: LightCoord,
+                       root_id: RootId,
+                       name: Vec<u8>) -> Result<ZoneId, dispatch::DispatchError> {
+        ensure!(name.len() <= T::MaxZoneNameLen::get() as usize, Error::<T>::InvalidData);
+        let (area_id, zone_id) = Self::find_zone_slot(&who, rect, height, root_id)?;
+        Self::ensure_area_settled(root_id, area_id)?;
+        Self::note_caller_operation(&who)?;
+        Self::store_zone(who, root_id, area_id, zone_id, rect, height, name);
+        Ok(zone_id)
+    }
+
+    /// Stores zone into found slot, creating area, if this is the first zone inside.
+    /// Zones, added by root origin, are not indexed by author.
+    fn store_zone(who: Option<T::AccountId>,
+                  root_id: RootId,
+                  area_id: AreaId,
+                  zone_id: ZoneId,
+                  rect: Rect2D<T::Coord>,
+                  height: LightCoord,
+                  name: Vec<u8>) {
+        if !AreaData::contains_key(root_id, area_id) {
+            AreaData::insert(root_id, area_id, Area::new(GREEN_AREA));
+        }
+        RedZones::<T>::insert(zone_id, ZoneOf::<T>::new(zone_id, rect, height).with_name(name.clone()));
+        if let Some(author) = &who {
+            ZoneAuthor::<T>::insert(zone_id, author.clone());
+            AuthorZones::<T>::insert(author, zone_id, true);
+        }
+        TotalZones::mutate(|total| *total += 1);
+        Self::shift_density(root_id, area_id, true);
+        let now = <frame_system::Module<T>>::block_number();
+        ZoneCreatedAt::<T>::insert(zone_id, now);
+        ZonesByCreationBlock::<T>::insert(now, zone_id, ());
+        AreaLastChange::<T>::insert(root_id, area_id, now);
+        Self::deposit_zone_created(root_id, area_id, zone_id, who, rect, height, name);
+    }
+
+    /// Removes zone from storage together with all its records, returns removed zone.
+    /// Zone is archived, if enabled, so every removal path keeps its final state.
+    fn purge_zone(zone_id: ZoneId, who: Option<T::AccountId>) -> ZoneOf<T> {
+        let zone = RedZones::<T>::take(zone_id);
+        let (root_id, area_id, _) = Self::unpack_index(zone_id);
+        Self::forget_author(zone_id);
+        ZoneAttestations::<T>::remove(zone_id);
+        FrozenZones::remove(zone_id);
+        // Soft deleted zones are already excluded from density
+        if !DeletedZones::take(zone_id) {
+            Self::shift_density(root_id, area_id, false);
+        }
+        if let Some(created_at) = ZoneCreatedAt::<T>::take(zone_id) {
+            ZonesByCreationBlock::<T>::remove(created_at, zone_id);
+        }
+        ZoneExpiresAt::<T>::remove(zone_id);
+        TotalZones::mutate(|total| *total = total.saturating_sub(1));
+        AreaLastChange::<T>::insert(root_id, area_id, <frame_system::Module<T>>::block_number());
+        if T::ArchiveRemovedZones::get() {
+            Self::archive_zone(zone_id, zone.clone(), who);
+        }
+        zone
+    }
+
+    /// Weight of purge_zone: zone, author, deletion mark, creation block, density and total counter are read,
+    /// then zone records, author and creation indexes, density, counter, area change block, and archive are written
+    fn purge_zone_weight() -> Weight {
+        let archive_writes = if T::ArchiveRemovedZones::get() { 2 } else { 0 };
+        T::DbWeight::get().reads_writes(6, 14 + archive_writes)
+    }
+
+    /// Removes zone from author indexes
+    fn forget_author(zone_id: ZoneId) {
+        if let Some(author) = ZoneAuthor::<T>::take(zone_id) {
+            AuthorZones::<T>::remove(author, zone_id);
+        }
+    }
+
+    /// Whether zone, added by `who`, may exceed MaxTotalZones. None stands for root origin.
+    fn is_exempt_from_zone_cap(who: &Option<T::AccountId>) -> bool {
+        T::AdminsExemptFromMaxTotalZones::get() && match who {
+            Some(account) => <accounts::Module<T>>::account_is(account, ADMIN_ROLE.into()),
+            None => true,
+        }
+    }
+
+    /// Fails, if zones in the area were created or removed less than RegionCooldownBlocks ago
+    fn ensure_area_settled(root_id: RootId, area_id: AreaId) -> Result<(), Error<T>> {
+        if let Some(changed_at) = AreaLastChange::<T>::get(root_id, area_id) {
+            let now = <frame_system::Module<T>>::block_number();
+            ensure!(now >= changed_at + T::RegionCooldownBlocks::get(), Error::<T>::RegionCoolingDown);
+        }
+        Ok(())
+    }
+
+    /// Validates zone creation and finds area and id for a new zone.
+    /// Caller authorization and area cooldown are checked by dispatchables themselves,
+    /// `who` only decides, whether MaxTotalZones applies.
+    fn find_zone_slot(who: &Option<T::AccountId>,
+                      rect: Rect2D<T::Coord>,
+                      height: LightCoord,
+                      root_id: RootId) -> Result<(AreaId, ZoneId), Error<T>> {
+        ensure!(RootBoxes::<T>::contains_key(root_id), Error::<T>::RootDoesNotExist);
+        ensure!(height < T::MaxHeight::get(), Error::<T>::InvalidData);
+        let new_box = Self::zone_box(&Zone::new(0, rect, height));
+        ensure!(Self::is_valid_box(&new_box), Error::<T>::InvalidData);
+        if let Some(bounds) = MapBounds::<T>::get() {
+            ensure!(bounds.contains_box(new_box), Error::<T>::OutsideMapBounds);
+        }
+        ensure!(TotalZones::get() < T::MaxTotalZones::get() || Self::is_exempt_from_zone_cap(who),
+                Error::<T>::MapFull);
+        // Check if zone lies in one single area 
+        let area_id = RootBoxes::<T>::get(root_id).detect_intersected_area(rect.south_west);
+        let se_area_id = RootBoxes::<T>::get(root_id).detect_intersected_area(rect.north_east);
+        ensure!(area_id == se_area_id, Error::<T>::ZoneDoesntFit);
+
+        let max_zones = T::MaxBuildingsInArea::get();
+        let first_empty_id = Self::pack_index(root_id, area_id, 0);
+        // This is first zone in area, we don't need to check any intersections and stuff.
+        if !AreaData::contains_key(root_id, area_id) {
+            return Ok((area_id, first_empty_id));
+        }
+
+        // If area already exists, we check if it's full, and check all zones inside for intersection
+        ensure!(AreaData::get(root_id, area_id).area_type == GREEN_AREA, Error::<T>::ForbiddenArea); 
+        let mut zone_id = first_empty_id;
+        let mut current_zone = first_empty_id;
+        let mut empty_id_found = false;
+        // Maybe, this cycle should be splitted in two. One finds first unused Id,
+        // and only if it was found, we should look for intersections. Not sure.
+        while current_zone < first_empty_id + max_zones as ZoneId {
+            if RedZones::<T>::contains_key(current_zone) || empty_id_found {
+                // Check if our zone overlaps with another zone in current area
+                let box_to_check = Self::zone_box(&RedZones::<T>::get(current_zone));
+                ensure!(!Self::boxes_intersect(box_to_check, new_box), Error::<T>::OverlappingZone);
+                current_zone += 1;
+            } else { 
+                zone_id = current_zone;
+                empty_id_found = true;
+            }
+        } 
+        ensure!(empty_id_found, Error::<T>::AreaFull);
+        Ok((area_id, zone_id))
+    }
+
+    /// Returns altitude ranges at given position, which are free of red zones.
+    /// Zones rise from the ground, so there is at most one range: from the
+    /// highest zone, including RestrictedAltitudeBuffer, up to MaxHeight.
+    pub fn clear_altitude_range(lat: T::Coord, lon: T::Coord) -> Vec<(T::Coord, T::Coord)> {
+        let ground = Point3D::new(lat, lon, T::Coord::default());
+        let max_height = T::MaxHeight::get();
+        let ceiling = match Self::locate(ground) {
+            Some((root_id, area_id)) => {
+                if AreaData::contains_key(root_id, area_id) && AreaData::get(root_id, area_id).area_type != GREEN_AREA {
+                    return Vec::new();
+                }
+                Self::enforced_zones_in_area(root_id, area_id)
+                    .into_iter()
+                    .map(|zone_id| RedZones::<T>::get(zone_id))
+                    .filter(|zone| Self::zone_contains(zone, ground))
+                    .map(|zone| Self::restricted_ceiling(&zone))
+                    .max()
+                    .unwrap_or(0)
+            },
+            None => match T::OutOfWorldPolicy::get() {
+                OutOfWorldPolicy::Restricted => return Vec::new(),
+                OutOfWorldPolicy::Unrestricted => 0,
+            },
+        };
+        if ceiling >= max_height {
+            return Vec::new();
+        }
+        vec![(T::Coord::from_int(ceiling), T::Coord::from_int(max_height))]
+    }
+
+    /// Returns up to `count` ground points inside region, which are not restricted.
+    /// Points are sampled on a grid with root delta step, shifted by half of a step from region south west corner.
+    /// Count is clipped by MAX_LANDING_SPOTS, and amount of sampled points by MAX_AREAS_PER_QUERY.
+    pub fn safe_landing_spots(region: Rect2D<T::Coord>, count: u32) -> Vec<Point3D<T::Coord>> {
+        let ground = T::Coord::default();
+        let corner = Point3D::new(region.south_west.lat, region.south_west.lon, ground);
+        let root_id = Self::bitmap_root_at(corner);
+        if root_id == 0 || !RootBoxes::<T>::contains_key(root_id) {
+            return Vec::new();
+        }
+        let delta = RootBoxes::<T>::get(root_id).delta;
+        let half_step = delta / T::Coord::from_int(2);
+        let count = min(count, MAX_LANDING_SPOTS) as usize;
+
+        let mut output = Vec::new();
+        let mut samples: u16 = 0;
+        let mut lat = region.south_west.lat + half_step;
+        while lat < region.north_east.lat {
+            let mut lon = region.south_west.lon + half_step;
+            while lon < region.north_east.lon {
+                if output.len() >= count || samples >= MAX_AREAS_PER_QUERY {
+                    return output;
+                }
+                samples += 1;
+                let point = Point3D::new(lat, lon, ground);
+                if !Self::is_restricted(point) {
+                    output.push(point);
+                }
+                lon = lon + delta;
+            }
+            lat = lat + delta;
+        }
+        output
+    }
+
+    /// Finds path between two areas of a root through adjacent areas, which has the lowest cost.
+    /// Each zone inside an area adds ZONE_PATH_COST, areas, which are not green, can't be passed.
+    /// Search is stopped after MAX_AREAS_PER_QUERY areas, in this case None is returned.
+    pub fn cheapest_block_path(root_id: RootId, from_area: AreaId, to_area: AreaId) -> Option<Vec<AreaId>> {
+        if !RootBoxes::<T>::contains_key(root_id) {
+            return None;
+        }
+        let root = RootBoxes::<T>::get(root_id);
+        let max_area = root.get_max_area();
+        let total_rows = root.bounding_box.projection_on_plane().get_dimensions().lat.integer_division_u16(root.delta);
+        if total_rows == 0 || from_area == 0 || to_area == 0 || from_area > max_area || to_area > max_area {
+            return None;
+        }
+        Self::area_path_cost(root_id, from_area)?;
+        Self::area_path_cost(root_id, to_area)?;
+
+        let mut costs: BTreeMap<AreaId, u32> = BTreeMap::new();
+        let mut previous: BTreeMap<AreaId, AreaId> = BTreeMap::new();
+        let mut queue: BTreeSet<(u32, AreaId)> = BTreeSet::new();
+        costs.insert(from_area, 0);
+        queue.insert((0, from_area));
+        let mut visited: u16 = 0;
+        loop {
+            let (cost, area_id) = match queue.iter().next() {
+                Some(entry) => *entry,
+                None => return None,
+            };
+            queue.remove(&(cost, area_id));
+            if area_id == to_area {
+                break;
+            }
+            visited += 1;
+            if visited > MAX_AREAS_PER_QUERY {
+                return None;
+            }
+            // Areas are numbered by rows inside a column, starting from 1
+            let row = (area_id - 1) % total_rows;
+            let mut neighbours = Vec::new();
+            if row > 0 { neighbours.push(area_id - 1); }
+            if row + 1 < total_rows { neighbours.push(area_id + 1); }
+            if area_id > total_rows { neighbours.push(area_id - total_rows); }
+            if let Some(next_column) = area_id.checked_add(total_rows).filter(|next| *next <= max_area) {
+                neighbours.push(next_column);
+            }
+            for neighbour in neighbours {
+                let step = match Self::area_path_cost(root_id, neighbour) {
+                    Some(step) => step,
+                    None => continue,
+                };
+                let new_cost = cost.saturating_add(step);
+                if costs.get(&neighbour).map_or(true, |known| new_cost < *known) {
+                    if let Some(old_cost) = costs.insert(neighbour, new_cost) {
+                        queue.remove(&(old_cost, neighbour));
+                    }
+                    queue.insert((new_cost, neighbour));
+                    previous.insert(neighbour, area_id);
+                }
+            }
+        }
+
+        let mut path = vec![to_area];
+        let mut current = to_area;
+        while current != from_area {
+            current = previous[&current];
+            path.push(current);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Cost of passing through an area, None if area can't be passed
+    fn area_path_cost(root_id: RootId, area_id: AreaId) -> Option<u32> {
+        if AreaData::contains_key(root_id, area_id) && AreaData::get(root_id, area_id).area_type != GREEN_AREA {
+            return None;
+        }
+        Some(1 + ZONE_PATH_COST * Self::visible_zones_in_area(root_id, area_id).len() as u32)
+    }
+
+    /// Returns area, which holds most zones on the whole map, and amount of zones in it.
+    /// Soft deleted zones are not counted. Densities are looked up from the highest one, which
+    /// takes at most MaxBuildingsInArea reads. Ties are resolved to any of the densest areas.
+    pub fn densest_region() -> Option<(RootId, AreaId, u32)> {
+        (1..=T::MaxBuildingsInArea::get() as u32)
+            .rev()
+            .find_map(|count| AreasByDensity::iter_prefix(count)
+                .next()
+                .map(|((root_id, area_id), _)| (root_id, area_id, count)))
+    }
+
+    /// Changes AreaDensity by one zone and moves the area into the matching AreasByDensity group
+    fn shift_density(root_id: RootId, area_id: AreaId, grow: bool) {
+        let old = AreaDensity::get(root_id, area_id);
+        let new = if grow { old.saturating_add(1) } else { old.saturating_sub(1) };
+        if old > 0 {
+            AreasByDensity::remove(old, (root_id, area_id));
+        }
+        if new > 0 {
+            AreasByDensity::insert(new, (root_id, area_id), ());
+            AreaDensity::insert(root_id, area_id, new);
+        } else {
+            AreaDensity::remove(root_id, area_id);
+        }
+    }
+
+    /// Returns zones, which rect lies flush against the edge of root bounding box.
+    /// Such zones might be clipped by the root, so they're worth auditing.
+    /// Zones rise from the ground, so only horizontal edges are checked.
+    /// Only first MAX_AREAS_PER_QUERY areas of the root are scanned.
+    pub fn boundary_zones(root_id: RootId) -> Vec<ZoneId> {
+        if !RootBoxes::<T>::contains_key(root_id) {
+            return Vec::new();
+        }
+        let bounds = RootBoxes::<T>::get(root_id).bounding_box;
+        AreaData::iter_prefix(root_id)
+            .take(MAX_AREAS_PER_QUERY as usize)
+            .flat_map(|(area_id, _)| Self::visible_zones_in_area(root_id, area_id))
+            .filter(|zone_id| {
+                let rect = RedZones::<T>::get(zone_id).rect;
+                rect.south_west.lat == bounds.south_west.lat ||
+                rect.south_west.lon == bounds.south_west.lon ||
+                rect.north_east.lat == bounds.north_east.lat ||
+                rect.north_east.lon == bounds.north_east.lon
+            })
+            .collect()
+    }
+
+    /// Returns ids of all zones stored in the given area
+    pub fn zones_in_area(root_id: RootId, area_id: AreaId) -> Vec<ZoneId> {
+        let first_zone = Self::pack_index(root_id, area_id, 0);
+        let max_zones = T::MaxBuildingsInArea::get() as ZoneId;
+        (first_zone..first_zone + max_zones)
+            .filter(|zone_id| RedZones::<T>::contains_key(zone_id))
+            .collect()
+    }
+
+    /// Returns id, which zone_add would assign to the next zone in given area: freed slots are reused first.
+    /// Any zone added or removed in the area meanwhile invalidates the prediction.
+    /// None is returned, if area is full.
+    pub fn next_allocatable_id(root_id: RootId, area_id: AreaId) -> Option<ZoneId> {
+        let first_zone = Self::pack_index(root_id, area_id, 0);
+        let max_zones = T::MaxBuildingsInArea::get() as ZoneId;
+        (first_zone..first_zone + max_zones).find(|zone_id| !RedZones::<T>::contains_key(zone_id))
+    }
+
+    /// Returns amount of zones in each area from `from_area` to `to_area` inclusive.
+    /// Range is clipped by root size and by MAX_AREAS_PER_QUERY.
+    pub fn occupancy_histogram(root_id: RootId, from_area: AreaId, to_area: AreaId) -> Vec<(AreaId, u32)> {
+        if !RootBoxes::<T>::contains_key(root_id) {
+            return Vec::new();
+        }
+        let max_area = RootBoxes::<T>::get(root_id).get_max_area();
+        let first_area = max(from_area, 1);
+        let last_area = min(min(to_area, max_area), first_area.saturating_add(MAX_AREAS_PER_QUERY - 1));
+        (first_area..=last_area)
+            .map(|area_id| (area_id, Self::zones_in_area(root_id, area_id).len() as u32))
+            .collect()
+    }
+
+    /// Returns volume of intersection of two zones, or zero if they are disjoint.
+    /// Zones rise from the ground, so volume is lat * lon * height, where lat and lon
+    /// are counted in smallest Coord steps, see box_volume.
+    pub fn overlap_volume(a: ZoneId, b: ZoneId) -> Result<u128, Error<T>> {
+        ensure!(RedZones::<T>::contains_key(a), Error::<T>::ZoneDoesntExist);
+        ensure!(RedZones::<T>::contains_key(b), Error::<T>::ZoneDoesntExist);
+        let zone_a = RedZones::<T>::get(a);
+        let zone_b = RedZones::<T>::get(b);
+        if !zone_a.rect.intersects_rect(zone_b.rect) {
+            return Ok(0);
+        }
+        let (rect_a, rect_b) = (zone_a.rect, zone_b.rect);
+        let lat = min(rect_a.north_east.lat, rect_b.north_east.lat) - max(rect_a.south_west.lat, rect_b.south_west.lat);
+        let lon = min(rect_a.north_east.lon, rect_b.north_east.lon) - max(rect_a.south_west.lon, rect_b.south_west.lon);
+
+        Self::box_volume(lat, lon, min(zone_a.height, zone_b.height)).ok_or(Error::<T>::InvalidData)
+    }
+
+    /// Volume of a box with given sides, where lat and lon are counted in smallest Coord steps,
+    /// so fractional degrees are kept exact. None, if the product doesn't fit into u128.
+    fn box_volume(lat: T::Coord, lon: T::Coord, height: LightCoord) -> Option<u128> {
+        Self::coord_steps(lat)?
+            .checked_mul(Self::coord_steps(lon)?)?
+            .checked_mul(height as u128)
+    }
+
+    /// Amount of smallest Coord steps in a non negative length. Length is converted through BigCoord,
+    /// so it doesn't overflow Coord on the way.
+    fn coord_steps(length: T::Coord) -> Option<u128> {
+        if length < T::Coord::default() {
+            return None;
+        }
+        Some(length.try_into().integer_division_u32(T::Coord::from_raw(1).try_into()) as u128)
+    }
+
+    /// Returns total volume of zones, added by given account, counted same way as overlap_volume.
+    /// None, if the sum doesn't fit into u128.
+    pub fn owner_covered_volume(owner: &T::AccountId) -> Option<u128> {
+        AuthorZones::<T>::iter_prefix(owner)
+            .map(|(zone_id, _)| RedZones::<T>::get(zone_id))
+            .try_fold(0u128, |total, zone| total.checked_add(Self::zone_box_volume(&zone)?))
+    }
+
+    /// Returns zone authors together with amount of their zones, which are not soft deleted,
+    /// skipping first `offset` authors. Zones, added by root origin, have no author and are not counted.
+    /// Amount of authors is clipped by MAX_AUTHORS_PER_QUERY, use offset to get the next page.
+    pub fn owner_distribution(offset: u32, limit: u32) -> Vec<(T::AccountId, u32)> {
+        let wanted = offset.saturating_add(min(limit, MAX_AUTHORS_PER_QUERY)) as usize;
+        let mut owners: Vec<(T::AccountId, u32)> = Vec::new();
+        // Author is the first key, so zones of every author are iterated one after another
+        for (author, zone_id, _) in AuthorZones::<T>::iter() {
+            if DeletedZones::get(zone_id) {
+                continue;
+            }
+            match owners.last_mut() {
+                Some((last, count)) if *last == author => *count += 1,
+                _ => {
+                    if owners.len() == wanted {
+                        break;
+                    }
+                    owners.push((author, 1));
+                }
+            }
+        }
+        owners.into_iter().skip(offset as usize).collect()
+    }
+
+    /// Returns zones, which authors are no longer enabled in accounts registry, skipping first `offset` of them.
+    /// Both disabled and reaped accounts leave the registry, so zones of either are listed.
+    /// Amount of zones is clipped by MAX_ZONES_PER_QUERY, use offset to get the next page.
+    pub fn orphaned_zones(offset: u32, limit: u32) -> Vec<ZoneId> {
+        ZoneAuthor::<T>::iter()
+            .filter(|(_, author)| !<accounts::Module<T>>::account_registry(author).is_enabled())
+            .map(|(zone_id, _)| zone_id)
+            .skip(offset as usize)
+            .take(min(limit, MAX_ZONES_PER_QUERY) as usize)
+            .collect()
+    }
+
+    /// Returns volume of a zone in smallest Coord steps, see box_volume
+    fn zone_box_volume(zone: &ZoneOf<T>) -> Option<u128> {
+        let lat = zone.rect.north_east.lat - zone.rect.south_west.lat;
+        let lon = zone.rect.north_east.lon - zone.rect.south_west.lon;
+        Self::box_volume(lat, lon, zone.height)
+    }
+
+    /// Returns total volume of enforced zones on the map. Frozen and soft deleted zones are skipped.
+    /// Zones of overlapping roots are counted twice, if they overlap too.
+    /// Volume is counted same way as overlap_volume, and saturates at u128::MAX.
+    pub fn total_red_volume() -> u128 {
+        RedZones::<T>::iter()
+            .filter(|(zone_id, _)| Self::is_enforced(*zone_id))
+            .fold(0u128, |total, (_, zone)| {
+                total.saturating_add(Self::zone_box_volume(&zone).unwrap_or(u128::MAX))
+            })
+    }
+
+    /// Returns zones, sharing full or partial face with given zone. Zones touching only by corner are skipped.
+    pub fn adjacent_zones(zone_id: ZoneId) -> Vec<ZoneId> {
+        if !RedZones::<T>::contains_key(zone_id) {
+            return Vec::new();
+        }
+        let rect = RedZones::<T>::get(zone_id).rect;
+        let (root_id, _, _) = Self::unpack_index(zone_id);
+        if !RootBoxes::<T>::contains_key(root_id) {
+            return Vec::new();
+        }
+        let root = RootBoxes::<T>::get(root_id);
+        // Neighbours might lie in adjacent areas, so probe points right outside of the rect
+        let epsilon = T::Coord::from_raw(1);
+        let center = Point2D::new(
+            rect.south_west.lat + (rect.north_east.lat - rect.south_west.lat) / T::Coord::from_int(2),
+            rect.south_west.lon + (rect.north_east.lon - rect.south_west.lon) / T::Coord::from_int(2),
+        );
+        let lats = [rect.south_west.lat - epsilon, center.lat, rect.north_east.lat + epsilon];
+        let lons = [rect.south_west.lon - epsilon, center.lon, rect.north_east.lon + epsilon];
+        let mut areas = Vec::new();
+        for lat in lats.iter() {
+            for lon in lons.iter() {
+                let area_id = root.detect_intersected_area(Point2D::new(*lat, *lon));
+                if area_id != 0 && !areas.contains(&area_id) {
+                    areas.push(area_id);
+                }
+            }
+        }
+
+        let mut output = Vec::new();
+        for area_id in areas {
+            for neighbour_id in Self::visible_zones_in_area(root_id, area_id) {
+                if neighbour_id != zone_id && Self::rects_share_face(rect, RedZones::<T>::get(neighbour_id).rect) {
+                    output.push(neighbour_id);
+                }
+            }
+        }
+        output.sort();
+        output
+    }
+
+    /// True, if rects touch each other by an edge segment of non-zero length
+    fn rects_share_face(a: Rect2D<T::Coord>, b: Rect2D<T::Coord>) -> bool {
+        let lat_overlap = max(a.south_west.lat, b.south_west.lat) < min(a.north_east.lat, b.north_east.lat);
+        let lon_overlap = max(a.south_west.lon, b.south_west.lon) < min(a.north_east.lon, b.north_east.lon);
+        let lat_touch = a.north_east.lat == b.south_west.lat || a.south_west.lat == b.north_east.lat;
+        let lon_touch = a.north_east.lon == b.south_west.lon || a.south_west.lon == b.north_east.lon;
+
+        (lat_touch && lon_overlap) || (lon_touch && lat_overlap)
+    }
+
+    /// Gets sphere enclosing zone box, from the ground up to zone height [center, squared radius]
+    pub fn zone_bounding_sphere(zone_id: ZoneId) -> Option<(Point3D<T::Coord>, T::BigCoord)> {
+        if !RedZones::<T>::contains_key(zone_id) {
+            return None;
+        }
+        Some(Self::zone_box(&RedZones::<T>::get(zone_id)).bounding_sphere())
+    }
+
+    /// Gets the box, occupied by a zone: zones rise from the ground up to their height.
+    pub fn zone_box(zone: &ZoneOf<T>) -> Box3D<T::Coord> {
+        Box3D::new(
+            Point3D::new(zone.rect.south_west.lat, zone.rect.south_west.lon, T::Coord::default()),
+            Point3D::new(zone.rect.north_east.lat, zone.rect.north_east.lon, T::Coord::from_int(zone.height)),
+        )
+    }
+
+    /// Returns zones, which boxes intersect given region, skipping first `offset` of them.
+    /// Amount of zones is clipped by MAX_ZONES_PER_QUERY, use offset to get the next page.
+    /// None, if the region is too large to be scanned, see visible_zones_in_region.
+    pub fn zones_in_region(region: Box3D<T::Coord>, offset: u32, limit: u32) -> Option<Vec<ZoneOf<T>>> {
+        let zones = Self::visible_zones_in_region(region)?
+            .into_iter()
+            .map(RedZones::<T>::get)
+            .filter(|zone| Self::boxes_intersect(Self::zone_box(zone), region))
+            .skip(offset as usize)
+            .take(min(limit, MAX_ZONES_PER_QUERY) as usize)
+            .collect();
+        Some(zones)
+    }
+
+    /// Returns zones, which are not soft deleted, from every area under the region projection.
+    /// Only roots, owning bitmap cells of the region, are inspected. None, if the region covers more
+    /// than MAX_CELLS_PER_QUERY cells or MAX_AREAS_PER_QUERY areas, so at most
+    /// MAX_AREAS_PER_QUERY * MaxBuildingsInArea zone slots are read.
+    fn visible_zones_in_region(region: Box3D<T::Coord>) -> Option<Vec<ZoneId>> {
+        let rect = region.projection_on_plane();
+        let mut areas_left = MAX_AREAS_PER_QUERY;
+        let mut output = Vec::new();
+        for root_id in Self::roots_in_cells(region.south_west, region.north_east)? {
+            let areas = RootBoxes::<T>::get(root_id).areas_in_rect(rect, areas_left)?;
+            areas_left -= areas.len() as u16;
+            for area_id in areas {
+                output.extend(Self::visible_zones_in_area(root_id, area_id));
+            }
+        }
+        Some(output)
+    }
+
+    /// Returns summary of zones, which boxes intersect given region. Soft deleted zones are skipped.
+    /// None, if the region is too large to be scanned, see visible_zones_in_region.
+    pub fn region_stats(region: Box3D<T::Coord>) -> Option<RegionStats> {
+        let mut stats = RegionStats::default();
+        let mut owners = BTreeSet::new();
+        Self::visible_zones_in_region(region)?
+            .into_iter()
+            .map(|zone_id| (zone_id, RedZones::<T>::get(zone_id)))
+            .filter(|(_, zone)| Self::boxes_intersect(Self::zone_box(zone), region))
+            .for_each(|(zone_id, zone)| {
+                if FrozenZones::get(zone_id) {
+                    stats.frozen_zones += 1;
+                } else {
+                    stats.enforced_zones += 1;
+                }
+                let volume = Self::zone_box_volume(&zone).unwrap_or(u128::MAX);
+                stats.covered_volume = stats.covered_volume.saturating_add(volume);
+                if let Some(author) = ZoneAuthor::<T>::get(zone_id) {
+                    owners.insert(author);
+                }
+            });
+        stats.owners = owners.len() as u32;
+        Some(stats)
+    }
+
+    /// Returns enforced zones of the root, which altitude range overlaps band between `min_alt` and `max_alt`.
+    /// Touching the band by top or bottom is not an overlap. Only first MAX_AREAS_PER_QUERY areas
+    /// of the root are scanned, and amount of zones is clipped by MAX_ZONES_PER_QUERY.
+    pub fn zones_in_altitude_band(root_id: RootId, min_alt: T::Coord, max_alt: T::Coord) -> Vec<ZoneId> {
+        AreaData::iter_prefix(root_id)
+            .take(MAX_AREAS_PER_QUERY as usize)
+            .flat_map(|(area_id, _)| Self::enforced_zones_in_area(root_id, area_id))
+            .filter(|zone_id| {
+                let zone_box = Self::zone_box(&RedZones::<T>::get(zone_id));
+                zone_box.south_west.alt < max_alt && min_alt < zone_box.north_east.alt
+            })
+            .take(MAX_ZONES_PER_QUERY as usize)
+            .collect()
+    }
+
+    /// Returns zones, created at blocks from `from` to `to` inclusive, and not removed since.
+    /// Only first MAX_BLOCKS_PER_QUERY blocks of the range are inspected,
+    /// and amount of zones is clipped by MAX_ZONES_PER_QUERY.
+    pub fn zones_created_between(from: T::BlockNumber, to: T::BlockNumber) -> Vec<ZoneId> {
+        let mut zones = Vec::new();
+        let mut block = from;
+        for _ in 0..MAX_BLOCKS_PER_QUERY {
+            if block > to || zones.len() >= MAX_ZONES_PER_QUERY as usize {
+                break;
+            }
+            zones.extend(ZonesByCreationBlock::<T>::iter_prefix(block).map(|(zone_id, _)| zone_id));
+            // Checked before increment, so the last possible block doesn't overflow
+            if block == to {
+                break;
+            }
+            block += One::one();
+        }
+        zones.truncate(MAX_ZONES_PER_QUERY as usize);
+        zones
+    }
+
+    /// True, if boxes of two zones overlap. Touching faces are not a conflict.
+    pub fn zones_conflict(a: ZoneId, b: ZoneId) -> Result<bool, Error<T>> {
+        ensure!(RedZones::<T>::contains_key(a) && RedZones::<T>::contains_key(b), Error::<T>::ZoneDoesntExist);
+        let a_box = Self::zone_box(&RedZones::<T>::get(a));
+        let b_box = Self::zone_box(&RedZones::<T>::get(b));
+        Ok(Self::boxes_intersect(a_box, b_box))
+    }
+
+    /// Returns zone, which box contains given point, if any.
+    pub fn zone_at(point: Point3D<T::Coord>) -> Option<ZoneOf<T>> {
+        let (root_id, area_id) = Self::locate(point)?;
+        Self::visible_zones_in_area(root_id, area_id)
+            .into_iter()
+            .map(RedZones::<T>::get)
+            .find(|zone| Self::zone_box(zone).contains_point(point))
+    }
+
+    /// Suggests a box of the same size near the proposed one, which doesn't overlap any zone.
+    /// Candidates are shifted by whole box sizes, nearest ones first, up to SUGGESTION_SEARCH_RADIUS.
+    /// Returns proposed box itself, if it is already free, and None, if nothing fits nearby.
+    pub fn suggest_nonconflicting_box(proposed: Box3D<T::Coord>) -> Option<Box3D<T::Coord>> {
+        let lat_step = proposed.north_east.lat - proposed.south_west.lat;
+        let lon_step = proposed.north_east.lon - proposed.south_west.lon;
+        for radius in 0..=SUGGESTION_SEARCH_RADIUS {
+            for lat_shift in -radius..=radius {
+                for lon_shift in -radius..=radius {
+                    // Inner rings are already checked
+                    if max(lat_shift.abs(), lon_shift.abs()) != radius {
+                        continue;
+                    }
+                    let shift = |point: Point3D<T::Coord>| Point3D::new(
+                        Self::shift_coord(point.lat, lat_step, lat_shift),
+                        Self::shift_coord(point.lon, lon_step, lon_shift),
+                        point.alt,
+                    );
+                    let candidate = Box3D::new(shift(proposed.south_west), shift(proposed.north_east));
+                    if Self::box_is_free(candidate) {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn shift_coord(value: T::Coord, step: T::Coord, times: i32) -> T::Coord {
+        let offset = step * T::Coord::from_int(times.abs() as u32);
+        if times < 0 { value - offset } else { value + offset }
+    }
+
+    /// True, if box lies in a single green area and doesn't overlap any zone there
+    fn box_is_free(candidate: Box3D<T::Coord>) -> bool {
+        let (root_id, area_id) = match Self::locate(candidate.south_west) {
+            Some(location) => location,
+            None => return false,
+        };
+        if RootBoxes::<T>::get(root_id).detect_intersected_area(candidate.north_east.project()) != area_id {
+            return false;
+        }
+        if AreaData::contains_key(root_id, area_id) && AreaData::get(root_id, area_id).area_type != GREEN_AREA {
+            return false;
+        }
+        !Self::visible_zones_in_area(root_id, area_id)
+            .into_iter()
+            .any(|zone_id| Self::boxes_intersect(Self::zone_box(&RedZones::<T>::get(zone_id)), candidate))
+    }
+
+    /// True, if north east corner of the box is strictly above south west one on each axis.
+    /// Inverted and flat boxes break intersection checks, so they are rejected on input.
+    pub fn is_valid_box(b: &Box3D<T::Coord>) -> bool {
+        b.south_west.lat < b.north_east.lat &&
+        b.south_west.lon < b.north_east.lon &&
+        b.south_west.alt < b.north_east.alt
+    }
+
+    /// Overlap check for zone boxes. All zone conflict checks go through here,
+    /// so it can be backed by a spatial index later.
+    pub fn boxes_intersect(a: Box3D<T::Coord>, b: Box3D<T::Coord>) -> bool {
+        a.intersects_box(b)
+    }
+
+    /// Stores final state of removed zone, and schedules its pruning
+    fn archive_zone(zone_id: ZoneId, zone: ZoneOf<T>, who: Option<T::AccountId>) {
+        let now = <frame_system::Module<T>>::block_number();
+        ArchivedZones::<T>::insert(zone_id, now, (zone, who));
+        ArchiveExpiry::<T>::append(now + T::ArchiveRetention::get(), (zone_id, now));
+    }
+
+    /// Emits zone creation event, according to configured verbosity
+    fn deposit_zone_created(root_id: RootId, area_id: AreaId, zone_id: ZoneId,
+                            who: Option<T::AccountId>, rect: Rect2D<T::Coord>, height: LightCoord, name: Vec<u8>) {
+        let event = match T::EventVerbosity::get() {
+            EventVerbosity::Minimal => RawEvent::ZoneCreatedMinimal(zone_id),
+            EventVerbosity::Standard => RawEvent::ZoneCreated(root_id, area_id, zone_id, who, rect, height),
+            EventVerbosity::Full => RawEvent::ZoneCreatedFull(root_id, area_id, zone_id, who, rect, height, name),
+        };
+        Self::deposit_event(event);
+    }
+
+    /// Emits zone removal event, according to configured verbosity
+    fn deposit_zone_removed(zone_id: ZoneId, who: Option<T::AccountId>, zone: ZoneOf<T>) {
+        let event = match T::EventVerbosity::get() {
+            EventVerbosity::Minimal => RawEvent::ZoneRemovedMinimal(zone_id),
+            EventVerbosity::Standard => RawEvent::ZoneRemoved(zone_id, who),
+            EventVerbosity::Full => RawEvent::ZoneRemovedFull(zone_id, who, zone.rect, zone.height),
+        };
+        Self::deposit_event(event);
+    }
+
     /// Reverse function for pack_index()
     #[allow(dead_code)]
     fn unpack_index(index: ZoneId) -> (RootId, AreaId, u16) {
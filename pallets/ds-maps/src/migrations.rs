@@ -0,0 +1,39 @@
+//! Migrations of stored data between pallet releases
+
+use super::*;
+
+/// Zone layout before zone names were added
+#[derive(Encode, Decode, Clone, Default)]
+pub(crate) struct ZoneV1<Coord> {
+    pub zone_id: ZoneId,
+    pub rect: Rect2D<Coord>,
+    pub height: LightCoord,
+}
+
+/// Translates zones, stored in V1 layout, giving them empty names.
+/// TotalZones and area densities are counted from translated zones, as V1 didn't keep them.
+/// Does nothing, if storage is already migrated.
+///
+/// Every zone is translated within the upgrade block, which costs a read and up to three writes
+/// per zone. Runtime's MaxTotalZones is kept low enough for this to fit a single block.
+pub fn migrate_to_v2<T: Trait>() -> Weight {
+    if StorageVersion::get() != Releases::V1 {
+        return T::DbWeight::get().reads(1);
+    }
+    let mut translated: Weight = 0;
+    let mut densities: BTreeMap<(RootId, AreaId), u32> = BTreeMap::new();
+    RedZones::<T>::translate::<ZoneV1<T::Coord>, _>(|zone_id, old| {
+        translated += 1;
+        let (root_id, area_id, _) = Module::<T>::unpack_index(zone_id);
+        *densities.entry((root_id, area_id)).or_insert(0) += 1;
+        Some(Zone::new(old.zone_id, old.rect, old.height))
+    });
+    TotalZones::put(translated as u32);
+    let density_writes = 2 * densities.len() as Weight;
+    for ((root_id, area_id), density) in densities {
+        AreaDensity::insert(root_id, area_id, density);
+        AreasByDensity::insert(density, (root_id, area_id), ());
+    }
+    StorageVersion::put(Releases::V2);
+    T::DbWeight::get().reads_writes(translated + 1, translated + density_writes + 2)
+}
@@ -1,11 +1,13 @@
 #![allow(clippy::from_over_into)]
 
 use crate as pallet_ds_maps;
-use crate::Trait;
+use crate::{Trait, EventVerbosity, OutOfWorldPolicy};
 use frame_support::{
     construct_runtime, parameter_types,
+    traits::Get,
     weights::Weight,
 };
+use std::cell::RefCell;
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
@@ -83,8 +85,8 @@ impl crate::WeightInfo for WeightInfo {
     fn root_add() -> Weight {
         <() as crate::WeightInfo>::root_add()
     }
-    fn zone_add() -> Weight {
-        <() as crate::WeightInfo>::zone_add()
+    fn zone_add(z: u32) -> Weight {
+        <() as crate::WeightInfo>::zone_add(z)
     }
     fn root_remove() -> Weight {
         <() as crate::WeightInfo>::root_remove()
@@ -98,12 +100,77 @@ impl crate::WeightInfo for WeightInfo {
     fn route_add() -> Weight {
         <() as crate::WeightInfo>::route_add()
     }
+    fn set_coordinate_system() -> Weight {
+        <() as crate::WeightInfo>::set_coordinate_system()
+    }
+    fn zone_attest() -> Weight {
+        <() as crate::WeightInfo>::zone_attest()
+    }
+    fn zone_set_frozen() -> Weight {
+        <() as crate::WeightInfo>::zone_set_frozen()
+    }
+    fn zone_add_batch(zones: u32, z: u32) -> Weight {
+        <() as crate::WeightInfo>::zone_add_batch(zones, z)
+    }
+    fn zone_soft_delete() -> Weight {
+        <() as crate::WeightInfo>::zone_soft_delete()
+    }
+    fn map_init() -> Weight {
+        <() as crate::WeightInfo>::map_init()
+    }
 }
 
 // After researches, consider placing here max grid sizes
 parameter_types! {
     pub const MaxHeight: u16 = 400;
-    pub const MaxBuildingsInArea: u16 = 2;
+    pub const MaxAttestationLength: u32 = 64;
+    pub const MaxZoneNameLen: u32 = 16;
+    pub const ArchiveRemovedZones: bool = true;
+    pub const ArchiveRetention: u64 = 10;
+    pub const BlocksPerDay: u64 = 100;
+    pub const MaxZonesPerBatch: u32 = 4;
+    pub const MaxExpiriesPerBlock: u32 = 2;
+}
+
+/// Declares values, which can be switched inside a single test.
+/// Every value gets `set`, implements Get, and is reset to its default by reset_switchables.
+macro_rules! switchable {
+    ($($name:ident: $type:ty = $default:expr;)*) => {
+        $(
+            pub struct $name;
+            impl $name {
+                pub fn set(value: $type) {
+                    Self::with(|v| *v.borrow_mut() = value);
+                }
+                fn with<R>(f: impl FnOnce(&RefCell<$type>) -> R) -> R {
+                    thread_local! {
+                        static VALUE: RefCell<$type> = RefCell::new($default);
+                    }
+                    VALUE.with(f)
+                }
+            }
+            impl Get<$type> for $name {
+                fn get() -> $type {
+                    Self::with(|v| *v.borrow())
+                }
+            }
+        )*
+
+        fn reset_switchables() {
+            $( $name::set($default); )*
+        }
+    };
+}
+
+switchable! {
+    ZoneEventVerbosity: EventVerbosity = EventVerbosity::Standard;
+    OutOfWorld: OutOfWorldPolicy = OutOfWorldPolicy::Unrestricted;
+    MaxTotalZones: u32 = 16;
+    AdminsExemptFromMaxTotalZones: bool = false;
+    MaxBuildingsInArea: u16 = 2;
+    RestrictedAltitudeBuffer: u32 = 0;
+    MaxOpsPerDay: u32 = 1000;
+    RegionCooldownBlocks: u64 = 0;
 }
 
 impl Trait for Test {
@@ -114,6 +181,18 @@ impl Trait for Test {
     type RawCoord = i32;
     type MaxBuildingsInArea = MaxBuildingsInArea;
     type MaxHeight = MaxHeight;
+    type EventVerbosity = ZoneEventVerbosity;
+    type MaxTotalZones = MaxTotalZones;
+    type AdminsExemptFromMaxTotalZones = AdminsExemptFromMaxTotalZones;
+    type MaxAttestationLength = MaxAttestationLength;
+    type MaxZoneNameLen = MaxZoneNameLen;
+    type OutOfWorldPolicy = OutOfWorld;
+    type ArchiveRemovedZones = ArchiveRemovedZones;
+    type ArchiveRetention = ArchiveRetention;
+    type RestrictedAltitudeBuffer = RestrictedAltitudeBuffer;
+    type MaxZonesPerBatch = MaxZonesPerBatch;
+    type RegionCooldownBlocks = RegionCooldownBlocks;
+    type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
 }
 
 parameter_types! {
@@ -133,6 +212,7 @@ impl pallet_balances::Config for Test {
 
 parameter_types! {
     pub const AdminRole: u8 = ADMIN_ROLE;
+    pub const AutoReenableOnTopUp: bool = false;
 }
 
 impl pallet_ds_accounts::Trait for Test {
@@ -143,6 +223,9 @@ impl pallet_ds_accounts::Trait for Test {
     type WeightInfo = ();
     type SerialNumber = Vec<u8>;
     type MetaIPFS = Vec<u8>;
+    type AutoReenableOnTopUp = AutoReenableOnTopUp;
+    type MaxOpsPerDay = MaxOpsPerDay;
+    type BlocksPerDay = BlocksPerDay;
 }
 
 static INITIAL: [(
@@ -151,6 +234,7 @@ static INITIAL: [(
 ); 1] = [(1, ADMIN_ROLE)];
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
+    reset_switchables();
     let mut storage = system::GenesisConfig::default()
         .build_storage::<Test>()
         .unwrap();
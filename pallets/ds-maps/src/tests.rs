@@ -4,12 +4,20 @@ use crate::{
             Point3D, Box3D,
             Point2D, Rect2D,
             Waypoint,
+            EventVerbosity, RawEvent,
+            CoordinateSystem, Zone,
+            OutOfWorldPolicy,
+            ZoneId,
 };
 use frame_support::{
-    assert_noop, assert_ok,
+    assert_err, assert_noop, assert_ok,
+    dispatch::DispatchError,
+    storage::{unhashed, StorageMap, IterableStorageMap},
+    traits::{Get, OnInitialize, IntegrityTest},
 };
 use substrate_fixed::types::I10F22;
 use sp_std::str::FromStr;
+use frame_support::sp_runtime::Permill;
 
 // Explanation for all hardcoded values down here
 //                             Root        P2(55.921, 37.901)
@@ -60,6 +68,8 @@ type Moment = u64;
 // Constants to make tests more readable
 const ADMIN_ACCOUNT_ID: u64 = 1;
 const REGISTRAR_1_ACCOUNT_ID: u64 = 2;
+const REGISTRAR_2_ACCOUNT_ID: u64 = 3;
+const REGULATOR_ACCOUNT_ID: u64 = 5;
 pub const ROOT_ID: u64 = 0b0001_0101_1010_0001_0000_1110_1001_1001_0001_0101_1101_1000_0000_1110_1100_1110;
 // Values in construct_testing_..() pre-calculated
 // construct_custom_..() same functionality, but custom numbers
@@ -141,6 +151,43 @@ pub fn construct_custom_waypoints(start_lat: &str, start_lon: &str,
     vec![start_wp, end_wp]
 }
 
+/// Registers REGISTRAR_1 and adds the testing root on its behalf
+fn setup_testing_root() {
+    assert_ok!(
+        DSAccountsModule::account_add(
+            Origin::signed(ADMIN_ACCOUNT_ID),
+            REGISTRAR_1_ACCOUNT_ID,
+            super::REGISTRAR_ROLE
+    ));
+    assert_ok!(
+        DSMapsModule::root_add(
+            Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+            construct_testing_box(),
+            coord(DELTA),
+    ));
+}
+
+/// Same as setup_testing_root, then adds the testing zone by REGISTRAR_1. Returns id of the zone
+fn setup_testing_zone() -> ZoneId {
+    setup_testing_root();
+    assert_ok!(
+        DSMapsModule::zone_add(
+            Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+            construct_testing_rect(),
+            DEFAULT_HEIGHT,
+            ROOT_ID,
+    ));
+    DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0)
+}
+
+/// Stores zone by REGISTRAR_1 into given slot of AREA_ID without zone_add checks, so it may overlap
+/// other zones. Author, counters and density are updated same way as by zone_add. Returns id of the zone
+fn store_unchecked_zone(slot: u16, rect: Rect2D<Coord>, height: u32) -> ZoneId {
+    let zone_id = DSMapsModule::pack_index(ROOT_ID, AREA_ID, slot);
+    DSMapsModule::store_zone(Some(REGISTRAR_1_ACCOUNT_ID), ROOT_ID, AREA_ID, zone_id, rect, height, Vec::new());
+    zone_id
+}
+
 #[test]
 fn it_try_to_add_root_unauthorized() {
     new_test_ext().execute_with(|| {
@@ -412,6 +459,7 @@ fn it_try_to_remove_root() {
             DSMapsModule::root_remove(
                 Origin::signed(REGISTRAR_1_ACCOUNT_ID),
                 ROOT_ID,
+                0,
         ));
         let root = DSMapsModule::root_box_data(ROOT_ID);
         assert!(!root.is_active());
@@ -1145,4 +1193,1962 @@ fn it_add_lots_of_zones() {
             )
         );
     });
-}
\ No newline at end of file
+}
+
+fn last_event() -> Event {
+    System::events().pop().expect("Event expected").event
+}
+
+#[test]
+fn it_emits_zone_events_with_configured_verbosity() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+            )
+        );
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+            )
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+        let first_zone = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0);
+        assert_eq!(
+            last_event(),
+            Event::pallet_ds_maps(RawEvent::ZoneCreated(
+                ROOT_ID, AREA_ID, first_zone, Some(REGISTRAR_1_ACCOUNT_ID), construct_testing_rect(), DEFAULT_HEIGHT
+            ))
+        );
+
+        ZoneEventVerbosity::set(EventVerbosity::Minimal);
+        let second_rect = construct_custom_rect("55.392", "37.382", "55.393", "37.383");
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                second_rect,
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+        let second_zone = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 1);
+        assert_eq!(last_event(), Event::pallet_ds_maps(RawEvent::ZoneCreatedMinimal(second_zone)));
+
+        ZoneEventVerbosity::set(EventVerbosity::Full);
+        assert_ok!(
+            DSMapsModule::zone_remove(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                second_zone,
+            )
+        );
+        assert_eq!(
+            last_event(),
+            Event::pallet_ds_maps(RawEvent::ZoneRemovedFull(second_zone, Some(REGISTRAR_1_ACCOUNT_ID), second_rect, DEFAULT_HEIGHT))
+        );
+    });
+}
+
+#[test]
+fn it_checks_zone_creation_without_mutation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+            )
+        );
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+            )
+        );
+        assert_err!(
+            DSMapsModule::can_create(&ADMIN_ACCOUNT_ID, construct_testing_rect(), DEFAULT_HEIGHT, ROOT_ID)
+                .map_err(DispatchError::from),
+            Error::NotAuthorized
+        );
+        assert_ok!(DSMapsModule::can_create(&REGISTRAR_1_ACCOUNT_ID, construct_testing_rect(), DEFAULT_HEIGHT, ROOT_ID));
+        // Nothing was created by the check
+        assert!(!super::AreaData::contains_key(ROOT_ID, AREA_ID));
+
+        // Fill the area, MaxBuildingsInArea is 2 in mock
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_rect("55.392", "37.382", "55.393", "37.383"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+        assert_err!(
+            DSMapsModule::can_create(
+                &REGISTRAR_1_ACCOUNT_ID,
+                construct_custom_rect("55.398", "37.388", "55.399", "37.389"),
+                DEFAULT_HEIGHT,
+                ROOT_ID
+            ).map_err(DispatchError::from),
+            Error::AreaFull
+        );
+    });
+}
+
+#[test]
+fn it_sets_coordinate_system() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(DSMapsModule::coordinate_system(), CoordinateSystem::ScaledWGS84);
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+            )
+        );
+        assert_noop!(
+            DSMapsModule::set_coordinate_system(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                CoordinateSystem::LocalMeters,
+            ),
+            Error::NotAuthorized
+        );
+        assert_ok!(
+            DSMapsModule::set_coordinate_system(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                CoordinateSystem::LocalMeters,
+            )
+        );
+        assert_eq!(DSMapsModule::coordinate_system(), CoordinateSystem::LocalMeters);
+
+        System::set_block_number(1);
+        assert_ok!(DSMapsModule::set_coordinate_system(Origin::root(), CoordinateSystem::ScaledWGS84));
+        assert_eq!(DSMapsModule::coordinate_system(), CoordinateSystem::ScaledWGS84);
+        assert_eq!(
+            last_event(),
+            Event::pallet_ds_maps(RawEvent::CoordinateSystemChanged(CoordinateSystem::ScaledWGS84, None))
+        );
+    });
+}
+
+#[test]
+fn it_try_to_add_zone_to_full_map() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+            )
+        );
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+            )
+        );
+        // Pretend, that map is almost full
+        super::TotalZones::put(MaxTotalZones::get() - 1);
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+        assert_eq!(DSMapsModule::total_zones(), MaxTotalZones::get());
+        let second_rect = construct_custom_rect("55.392", "37.382", "55.393", "37.383");
+        assert_noop!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                second_rect,
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            ),
+            Error::MapFull
+        );
+        // Removal frees a slot
+        assert_ok!(
+            DSMapsModule::zone_remove(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0),
+            )
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                second_rect,
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+    });
+}
+
+#[test]
+fn it_exempts_admins_from_full_map_if_configured() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID,
+                super::REGISTRAR_ROLE | super::ADMIN_ROLE
+        ));
+        super::TotalZones::put(MaxTotalZones::get());
+        // Exemption is off by default
+        assert_noop!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_2_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            ),
+            Error::MapFull
+        );
+
+        AdminsExemptFromMaxTotalZones::set(true);
+        // Registrars, which are not admins, are still limited
+        assert_noop!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            ),
+            Error::MapFull
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_2_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::root(),
+                construct_custom_rect("55.392", "37.382", "55.393", "37.383"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        assert_eq!(DSMapsModule::total_zones(), MaxTotalZones::get() + 2);
+    });
+}
+
+#[test]
+fn it_counts_zones_in_areas() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+            )
+        );
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+            )
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_rect("55.392", "37.382", "55.393", "37.383"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+        assert_eq!(
+            DSMapsModule::occupancy_histogram(ROOT_ID, AREA_ID - 1, AREA_ID + 1),
+            vec![(AREA_ID - 1, 0), (AREA_ID, 2), (AREA_ID + 1, 0)]
+        );
+        assert!(DSMapsModule::occupancy_histogram(ROOT_ID + 1, 1, 10).is_empty());
+    });
+}
+
+#[test]
+fn it_computes_overlap_volume() {
+    new_test_ext().execute_with(|| {
+        let zone_id = setup_testing_zone();
+        let distant_id = DSMapsModule::create_zone(
+            Some(REGISTRAR_1_ACCOUNT_ID),
+            construct_custom_rect("55.411", "37.372", "55.416", "37.375"),
+            DEFAULT_HEIGHT,
+            ROOT_ID,
+            Vec::new(),
+        ).unwrap();
+        assert_eq!(DSMapsModule::overlap_volume(zone_id, distant_id).unwrap(), 0);
+
+        // Zones can't overlap, when added through zone_add
+        let overlapping_id = store_unchecked_zone(
+            1, construct_custom_rect("55.3955", "37.3855", "55.3965", "37.3865"), 20
+        );
+        assert_eq!(DSMapsModule::total_zones(), 3);
+        assert_eq!(DSMapsModule::area_density(ROOT_ID, AREA_ID), 2);
+        assert_eq!(DSMapsModule::author_of(overlapping_id), Some(REGISTRAR_1_ACCOUNT_ID));
+
+        // Volume is counted in smallest Coord steps, height is taken as is
+        let lat_steps = (coord::<Coord>("55.396") - coord::<Coord>("55.3955")).to_bits() as u128;
+        let lon_steps = (coord::<Coord>("37.386") - coord::<Coord>("37.3855")).to_bits() as u128;
+        assert_eq!(DSMapsModule::overlap_volume(zone_id, overlapping_id).unwrap(), lat_steps * lon_steps * 20);
+        assert_err!(
+            DSMapsModule::overlap_volume(zone_id, DSMapsModule::pack_index(ROOT_ID, AREA_ID, 2))
+                .map_err(DispatchError::from),
+            Error::ZoneDoesntExist
+        );
+    });
+}
+
+#[test]
+fn it_attests_zone() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+            )
+        );
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+            )
+        );
+        let zone_index = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0);
+        assert_noop!(
+            DSMapsModule::zone_attest(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                zone_index,
+                REGULATOR_ACCOUNT_ID,
+                vec![1, 2, 3],
+            ),
+            Error::ZoneDoesntExist
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+        assert_noop!(
+            DSMapsModule::zone_attest(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                zone_index,
+                REGULATOR_ACCOUNT_ID,
+                vec![0; 65],
+            ),
+            Error::InvalidData
+        );
+        assert_ok!(
+            DSMapsModule::zone_attest(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                zone_index,
+                REGULATOR_ACCOUNT_ID,
+                vec![1, 2, 3],
+            )
+        );
+        assert_eq!(DSMapsModule::zone_attestation(zone_index), Some((REGULATOR_ACCOUNT_ID, vec![1, 2, 3])));
+
+        assert_ok!(
+            DSMapsModule::zone_remove(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                zone_index,
+            )
+        );
+        assert_eq!(DSMapsModule::zone_attestation(zone_index), None);
+    });
+}
+
+#[test]
+fn it_checks_if_point_is_restricted() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+            )
+        );
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+            )
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+        let inside_zone = Point3D::new(coord("55.3955"), coord("37.3855"), coord("10"));
+        let above_zone = Point3D::new(coord("55.3955"), coord("37.3855"), coord("35"));
+        let green_space = Point3D::new(coord("55.398"), coord("37.388"), coord("10"));
+        let outside_root = Point3D::new(coord("10"), coord("10"), coord("10"));
+        assert!(DSMapsModule::is_restricted(inside_zone));
+        assert!(!DSMapsModule::is_restricted(above_zone));
+        assert!(!DSMapsModule::is_restricted(green_space));
+        assert!(!DSMapsModule::is_restricted(outside_root));
+
+        // Whole area becomes restricted, when it is not green anymore
+        assert_ok!(
+            DSMapsModule::change_area_type(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                ROOT_ID,
+                AREA_ID,
+                0,
+            )
+        );
+        assert!(DSMapsModule::is_restricted(green_space));
+    });
+}
+
+#[test]
+fn it_applies_out_of_world_policy() {
+    new_test_ext().execute_with(|| {
+        let outside_any_root = Point3D::new(coord("10"), coord("10"), coord("10"));
+        assert!(!DSMapsModule::is_restricted(outside_any_root));
+        OutOfWorld::set(OutOfWorldPolicy::Restricted);
+        assert!(DSMapsModule::is_restricted(outside_any_root));
+    });
+}
+
+#[test]
+fn it_finds_clear_altitude_range() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+            )
+        );
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+            )
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            )
+        );
+        let max_height: Coord = coord("400");
+        assert_eq!(
+            DSMapsModule::clear_altitude_range(coord("55.3955"), coord("37.3855")),
+            vec![(coord("30"), max_height)]
+        );
+        assert_eq!(
+            DSMapsModule::clear_altitude_range(coord("55.398"), coord("37.388")),
+            vec![(coord("0"), max_height)]
+        );
+    });
+}
+
+#[test]
+fn it_allows_root_origin_for_registrar_calls() {
+    new_test_ext().execute_with(|| {
+        setup_testing_zone();
+        assert_noop!(
+            DSMapsModule::change_area_type(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                ROOT_ID,
+                AREA_ID,
+                0
+            ),
+            Error::NotAuthorized
+        );
+        assert_noop!(
+            DSMapsModule::change_area_type(
+                Origin::none(),
+                ROOT_ID,
+                AREA_ID,
+                0
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(
+            DSMapsModule::change_area_type(
+                Origin::root(),
+                ROOT_ID,
+                AREA_ID,
+                0
+        ));
+        let zone_id = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0);
+        assert_noop!(
+            DSMapsModule::zone_attest(
+                Origin::signed(REGULATOR_ACCOUNT_ID),
+                zone_id,
+                REGULATOR_ACCOUNT_ID,
+                vec![1, 2, 3],
+            ),
+            Error::NotAuthorized
+        );
+        assert_ok!(
+            DSMapsModule::zone_attest(
+                Origin::root(),
+                zone_id,
+                REGULATOR_ACCOUNT_ID,
+                vec![1, 2, 3],
+        ));
+        assert_eq!(
+            last_event(),
+            Event::pallet_ds_maps(RawEvent::ZoneAttested(zone_id, REGULATOR_ACCOUNT_ID, None))
+        );
+        assert_ok!(DSMapsModule::root_remove(Origin::root(), ROOT_ID, 1));
+
+        assert_noop!(
+            DSMapsModule::root_add(
+                Origin::signed(REGULATOR_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+            ),
+            Error::NotAuthorized
+        );
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::root(),
+                construct_testing_box(),
+                coord(DELTA),
+        ));
+        assert_eq!(last_event(), Event::pallet_ds_maps(RawEvent::RootCreated(ROOT_ID, None)));
+        assert_ok!(DSMapsModule::root_remove(Origin::root(), ROOT_ID, 0));
+
+        let raw_coords: [i32; 6] = [
+            coord::<Coord>("55.371").to_bits(),
+            coord::<Coord>("37.371").to_bits(),
+            coord::<Coord>("1").to_bits(),
+            coord::<Coord>("55.921").to_bits(),
+            coord::<Coord>("37.901").to_bits(),
+            coord::<Coord>("3").to_bits(),
+        ];
+        assert_noop!(
+            DSMapsModule::raw_root_add(
+                Origin::signed(REGULATOR_ACCOUNT_ID),
+                raw_coords,
+                coord::<Coord>(DELTA).to_bits()
+            ),
+            Error::NotAuthorized
+        );
+        assert_ok!(
+            DSMapsModule::raw_root_add(
+                Origin::root(),
+                raw_coords,
+                coord::<Coord>(DELTA).to_bits()
+        ));
+        assert!(DSMapsModule::root_box_data(ROOT_ID).is_active());
+    });
+}
+
+#[test]
+fn it_finds_reachable_zones_along_heading() {
+    new_test_ext().execute_with(|| {
+        let zone_id = setup_testing_zone();
+        let start = Point3D::new(coord("55.3945"), coord("37.3855"), coord("10"));
+        let north = Point2D::new(coord("1"), coord("0"));
+        let south = Point2D::new(coord("-1"), coord("0"));
+        assert_eq!(DSMapsModule::reachable_zones(start, north, coord("0.002")), vec![zone_id]);
+        // Too short to reach the zone
+        assert!(DSMapsModule::reachable_zones(start, north, coord("0.0001")).is_empty());
+        // Zone is behind the start point
+        assert!(DSMapsModule::reachable_zones(start, south, coord("0.002")).is_empty());
+        // End point overflows Coord, or distance is out of bounds
+        assert!(DSMapsModule::reachable_zones(start, Point2D::new(coord("500"), coord("0")), coord("2")).is_empty());
+        assert!(DSMapsModule::reachable_zones(start, north, coord("17")).is_empty());
+        assert!(DSMapsModule::reachable_zones(start, north, coord("-0.002")).is_empty());
+    });
+}
+
+#[test]
+fn it_archives_removed_zone() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let zone_id = setup_testing_zone();
+        assert_ok!(DSMapsModule::zone_remove(Origin::signed(REGISTRAR_1_ACCOUNT_ID), zone_id));
+        assert!(!super::RedZones::<Test>::contains_key(zone_id));
+
+        let (zone, who) = DSMapsModule::archived_zone(zone_id, 1).unwrap();
+        assert_eq!(zone.rect, construct_testing_rect());
+        assert_eq!(zone.height, DEFAULT_HEIGHT);
+        assert_eq!(who, Some(REGISTRAR_1_ACCOUNT_ID));
+
+        // Reused id is archived under another removal block, first record is kept
+        System::set_block_number(2);
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        assert_eq!(DSMapsModule::zones_in_area(ROOT_ID, AREA_ID), vec![zone_id]);
+        assert_ok!(DSMapsModule::zone_remove(Origin::signed(REGISTRAR_1_ACCOUNT_ID), zone_id));
+        assert!(DSMapsModule::archived_zone(zone_id, 1).is_some());
+        assert!(DSMapsModule::archived_zone(zone_id, 2).is_some());
+
+        // Archive is pruned after retention window
+        DSMapsModule::on_initialize(1 + ArchiveRetention::get() - 1);
+        assert!(DSMapsModule::archived_zone(zone_id, 1).is_some());
+        DSMapsModule::on_initialize(1 + ArchiveRetention::get());
+        assert!(DSMapsModule::archived_zone(zone_id, 1).is_none());
+        assert!(DSMapsModule::archived_zone(zone_id, 2).is_some());
+    });
+}
+
+#[test]
+fn it_archives_zones_of_removed_root() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let zone_id = setup_testing_zone();
+        // Hint should cover every zone in the root
+        assert_noop!(
+            DSMapsModule::root_remove(Origin::signed(REGISTRAR_1_ACCOUNT_ID), ROOT_ID, 0),
+            Error::WeightHintTooLow
+        );
+        assert_ok!(DSMapsModule::root_remove(Origin::signed(REGISTRAR_1_ACCOUNT_ID), ROOT_ID, 1));
+
+        let (zone, who) = DSMapsModule::archived_zone(zone_id, 1).unwrap();
+        assert_eq!(zone.rect, construct_testing_rect());
+        assert_eq!(who, Some(REGISTRAR_1_ACCOUNT_ID));
+    });
+}
+
+#[test]
+fn it_predicts_zone_entries() {
+    new_test_ext().execute_with(|| {
+        let zone_id = setup_testing_zone();
+        let position = Point3D::new(coord("55.3945"), coord("37.3855"), coord("10"));
+        let velocity = Point2D::new(coord("0.001"), coord("0"));
+
+        let entries = DSMapsModule::predict_entries(position, velocity, coord("5"));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, zone_id);
+        // Rect south edge is 0.0005 away, which takes half of time unit
+        assert!((entries[0].1 - coord("0.5")).abs() < coord("0.001"));
+
+        // Horizon is too short
+        assert!(DSMapsModule::predict_entries(position, velocity, coord("0.4")).is_empty());
+        // Flying above the zone
+        let high_position = Point3D::new(coord("55.3945"), coord("37.3855"), coord("40"));
+        assert!(DSMapsModule::predict_entries(high_position, velocity, coord("5")).is_empty());
+        // End point overflows Coord, or horizon is out of bounds
+        assert!(DSMapsModule::predict_entries(position, Point2D::new(coord("100"), coord("0")), coord("5")).is_empty());
+        assert!(DSMapsModule::predict_entries(position, velocity, coord("17")).is_empty());
+    });
+}
+
+#[test]
+fn it_passes_integrity_test() {
+    new_test_ext().execute_with(|| {
+        DSMapsModule::integrity_test();
+    });
+}
+
+#[test]
+#[should_panic(expected = "MaxTotalZones must allow at least one zone")]
+fn it_fails_integrity_test_with_bad_constants() {
+    new_test_ext().execute_with(|| {
+        MaxTotalZones::set(0);
+        DSMapsModule::integrity_test();
+    });
+}
+
+#[test]
+fn it_finds_adjacent_zones() {
+    new_test_ext().execute_with(|| {
+        MaxBuildingsInArea::set(4);
+        setup_testing_root();
+        // Grid of four cells:
+        // +----+----+
+        // | nw | ne |
+        // +----+----+
+        // | sw | se |
+        // +----+----+
+        let cells = [
+            construct_custom_rect("55.392", "37.382", "55.393", "37.383"),
+            construct_custom_rect("55.393", "37.382", "55.394", "37.383"),
+            construct_custom_rect("55.392", "37.383", "55.393", "37.384"),
+            construct_custom_rect("55.393", "37.383", "55.394", "37.384"),
+        ];
+        for cell in cells.iter() {
+            assert_ok!(
+                DSMapsModule::zone_add(
+                    Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                    *cell,
+                    DEFAULT_HEIGHT,
+                    ROOT_ID,
+            ));
+        }
+        let zones = DSMapsModule::zones_in_area(ROOT_ID, AREA_ID);
+        assert_eq!(zones.len(), 4);
+        let (sw, nw, se, ne) = (zones[0], zones[1], zones[2], zones[3]);
+
+        assert_eq!(DSMapsModule::adjacent_zones(sw), vec![nw, se]);
+        assert_eq!(DSMapsModule::adjacent_zones(ne), vec![nw, se]);
+    });
+}
+
+#[test]
+fn it_restricts_altitude_buffer_above_zone() {
+    new_test_ext().execute_with(|| {
+        RestrictedAltitudeBuffer::set(10);
+        setup_testing_zone();
+        let within_buffer = Point3D::new(coord("55.3955"), coord("37.3855"), coord("35"));
+        let above_buffer = Point3D::new(coord("55.3955"), coord("37.3855"), coord("45"));
+        assert!(DSMapsModule::is_restricted(within_buffer));
+        assert!(!DSMapsModule::is_restricted(above_buffer));
+        assert_eq!(
+            DSMapsModule::clear_altitude_range(coord("55.3955"), coord("37.3855")),
+            vec![(coord("40"), coord("400"))]
+        );
+    });
+}
+
+#[test]
+fn it_skips_frozen_zone_in_enforcement() {
+    new_test_ext().execute_with(|| {
+        let zone_id = setup_testing_zone();
+        let inside_zone = Point3D::new(coord("55.3955"), coord("37.3855"), coord("10"));
+        assert_noop!(
+            DSMapsModule::route_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_waypoints(),
+                ROOT_ID,
+            ), Error::RouteIntersectRedZone
+        );
+        assert_noop!(
+            DSMapsModule::zone_set_frozen(Origin::signed(REGULATOR_ACCOUNT_ID), zone_id, true),
+            Error::NotAuthorized
+        );
+        // Registrar, who didn't add the zone, can't freeze it
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+        ));
+        assert_noop!(
+            DSMapsModule::zone_set_frozen(Origin::signed(REGISTRAR_2_ACCOUNT_ID), zone_id, true),
+            Error::NotAuthorized
+        );
+
+        System::set_block_number(1);
+        assert_ok!(DSMapsModule::zone_set_frozen(Origin::signed(ADMIN_ACCOUNT_ID), zone_id, true));
+        assert_eq!(last_event(), Event::pallet_ds_maps(RawEvent::ZoneFrozen(zone_id, true)));
+        assert_ok!(
+            DSMapsModule::route_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_waypoints(),
+                ROOT_ID,
+        ));
+        assert!(!DSMapsModule::is_restricted(inside_zone));
+        // Frozen zone is still listed
+        assert_eq!(DSMapsModule::zones_in_area(ROOT_ID, AREA_ID), vec![zone_id]);
+
+        assert_ok!(DSMapsModule::zone_set_frozen(Origin::signed(REGISTRAR_1_ACCOUNT_ID), zone_id, false));
+        assert!(DSMapsModule::is_restricted(inside_zone));
+    });
+}
+
+#[test]
+fn it_finds_safe_landing_spots() {
+    new_test_ext().execute_with(|| {
+        setup_testing_zone();
+        assert_ok!(
+            DSMapsModule::change_area_type(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                ROOT_ID,
+                AREA_ID,
+                0
+        ));
+        // Region covers two areas, one of them is restricted
+        let region = construct_custom_rect("55.391", "37.381", "55.401", "37.401");
+        let spots = DSMapsModule::safe_landing_spots(region, 5);
+        assert_eq!(spots.len(), 1);
+        let (root_id, area_id) = DSMapsModule::locate(spots[0]).unwrap();
+        assert_eq!(root_id, ROOT_ID);
+        assert_ne!(area_id, AREA_ID);
+        assert!(!DSMapsModule::is_restricted(spots[0]));
+
+        assert!(DSMapsModule::safe_landing_spots(region, 0).is_empty());
+    });
+}
+
+#[test]
+fn it_limits_daily_operations() {
+    new_test_ext().execute_with(|| {
+        MaxOpsPerDay::set(3);
+        System::set_block_number(1);
+        setup_testing_root();
+        // Calls of accounts pallet share the same counter
+        assert_ok!(
+            DSAccountsModule::register_pilot(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                REGULATOR_ACCOUNT_ID,
+                Vec::new(),
+        ));
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let second_rect = construct_custom_rect("55.392", "37.382", "55.393", "37.383");
+        assert_noop!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                second_rect,
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            ),
+            pallet_ds_accounts::Error::<Test>::DailyLimitReached
+        );
+
+        // Next day starts with a fresh counter
+        System::set_block_number(1 + BlocksPerDay::get());
+        assert_ok!(
+            DSMapsModule::change_area_type(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                ROOT_ID,
+                AREA_ID,
+                1,
+        ));
+        // Each zone of a batch is counted separately
+        let other_area_rect = construct_custom_rect("55.411", "37.372", "55.416", "37.375");
+        let third_rect = construct_custom_rect("55.397", "37.385", "55.398", "37.386");
+        assert_noop!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(second_rect, DEFAULT_HEIGHT), (other_area_rect, DEFAULT_HEIGHT), (third_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+            ),
+            pallet_ds_accounts::Error::<Test>::DailyLimitReached
+        );
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(second_rect, DEFAULT_HEIGHT), (other_area_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+        ));
+    });
+}
+
+#[test]
+fn it_computes_segment_restricted_fraction() {
+    new_test_ext().execute_with(|| {
+        setup_testing_zone();
+        // Second half of the segment is inside the zone
+        let from = Point3D::new(coord("55.3945"), coord("37.3855"), coord("10"));
+        let to = Point3D::new(coord("55.3955"), coord("37.3855"), coord("10"));
+        let fraction = DSMapsModule::segment_restricted_fraction(from, to);
+        assert!(fraction > Permill::from_parts(499_000) && fraction < Permill::from_parts(501_000));
+
+        let high_from = Point3D::new(coord("55.3945"), coord("37.3855"), coord("40"));
+        let high_to = Point3D::new(coord("55.3955"), coord("37.3855"), coord("40"));
+        assert_eq!(DSMapsModule::segment_restricted_fraction(high_from, high_to), Permill::from_parts(0));
+
+        // Segment, which leaves all roots, can't be checked, so it is treated as restricted
+        let outside = Point3D::new(coord("10"), coord("10"), coord("10"));
+        assert_eq!(DSMapsModule::segment_restricted_fraction(outside, to), Permill::one());
+        assert_eq!(DSMapsModule::segment_restricted_fraction(from, outside), Permill::one());
+    });
+}
+
+#[test]
+fn it_finds_cheapest_block_path_around_restricted_area() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        // Three areas in a row: start, blocked, destination
+        let area_of = |lat: &str, lon: &str| {
+            DSMapsModule::locate(Point3D::new(coord(lat), coord(lon), coord("0"))).unwrap().1
+        };
+        let start = area_of("55.396", "37.386");
+        let blocked = area_of("55.396", "37.396");
+        let destination = area_of("55.396", "37.406");
+
+        let direct_path = DSMapsModule::cheapest_block_path(ROOT_ID, start, destination).unwrap();
+        assert_eq!(direct_path, vec![start, blocked, destination]);
+
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_rect("55.395", "37.395", "55.396", "37.396"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        assert_ok!(
+            DSMapsModule::change_area_type(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                ROOT_ID,
+                blocked,
+                0
+        ));
+        let path = DSMapsModule::cheapest_block_path(ROOT_ID, start, destination).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&destination));
+        assert!(!path.contains(&blocked));
+
+        assert_eq!(DSMapsModule::cheapest_block_path(ROOT_ID, start, blocked), None);
+    });
+}
+
+#[test]
+fn it_builds_restriction_bitmap() {
+    new_test_ext().execute_with(|| {
+        setup_testing_zone();
+        let points = vec![
+            Point3D::new(coord("55.3955"), coord("37.3855"), coord("10")),
+            Point3D::new(coord("55.3955"), coord("37.3855"), coord("40")),
+            Point3D::new(coord("55.3945"), coord("37.3855"), coord("10")),
+            Point3D::new(coord("55.3956"), coord("37.3856"), coord("5")),
+        ];
+        assert_eq!(DSMapsModule::restriction_bitmap(&points), Some(vec![true, false, false, true]));
+        for point in points.iter() {
+            assert_eq!(DSMapsModule::restriction_bitmap(&[*point]).unwrap()[0], DSMapsModule::is_restricted(*point));
+        }
+
+        let max_points = vec![points[0]; super::MAX_POINTS_PER_QUERY];
+        assert_eq!(DSMapsModule::restriction_bitmap(&max_points).unwrap().len(), super::MAX_POINTS_PER_QUERY);
+        let many_points = vec![points[0]; super::MAX_POINTS_PER_QUERY + 1];
+        assert_eq!(DSMapsModule::restriction_bitmap(&many_points), None);
+    });
+}
+
+#[test]
+fn it_finds_zone_at_point() {
+    new_test_ext().execute_with(|| {
+        setup_testing_zone();
+        let inside = Point3D::new(coord("55.3955"), coord("37.3855"), coord("10"));
+        let zone = DSMapsModule::zone_at(inside).unwrap();
+        assert_eq!(zone.rect, construct_testing_rect());
+        // Lower bound is inclusive, upper is exclusive
+        let south_west = Point3D::new(coord("55.395"), coord("37.385"), coord("0"));
+        assert!(DSMapsModule::zone_at(south_west).is_some());
+        let north_east = Point3D::new(coord("55.396"), coord("37.386"), coord("10"));
+        assert!(DSMapsModule::zone_at(north_east).is_none());
+        let above = Point3D::new(coord("55.3955"), coord("37.3855"), coord("30"));
+        assert!(DSMapsModule::zone_at(above).is_none());
+    });
+}
+
+#[test]
+fn it_agrees_on_zone_boundary_points() {
+    new_test_ext().execute_with(|| {
+        setup_testing_zone();
+        let south_face = Point3D::new(coord("55.395"), coord("37.3855"), coord("10"));
+        let west_face = Point3D::new(coord("55.3955"), coord("37.385"), coord("10"));
+        let north_face = Point3D::new(coord("55.396"), coord("37.3855"), coord("10"));
+        let east_face = Point3D::new(coord("55.3955"), coord("37.386"), coord("10"));
+        let points = vec![south_face, west_face, north_face, east_face];
+        for point in points.iter() {
+            assert_eq!(DSMapsModule::is_restricted(*point), DSMapsModule::zone_at(*point).is_some());
+        }
+        assert_eq!(DSMapsModule::restriction_bitmap(&points), Some(vec![true, true, false, false]));
+    });
+}
+
+#[test]
+fn it_adds_zones_in_batch() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        let second_rect = construct_custom_rect("55.397", "37.385", "55.398", "37.386");
+        // Second zone overlaps the first one, so nothing is added
+        assert_noop!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(construct_testing_rect(), DEFAULT_HEIGHT), (construct_testing_rect(), DEFAULT_HEIGHT)],
+                ROOT_ID,
+            ),
+            Error::OverlappingZone
+        );
+        assert_noop!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(second_rect, DEFAULT_HEIGHT); 5],
+                ROOT_ID,
+            ),
+            Error::InvalidData
+        );
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(construct_testing_rect(), DEFAULT_HEIGHT), (second_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+        ));
+        assert_eq!(DSMapsModule::total_zones(), 2);
+        let first_zone = DSMapsModule::zone_at(Point3D::new(coord("55.3955"), coord("37.3855"), coord("10")));
+        let second_zone = DSMapsModule::zone_at(Point3D::new(coord("55.3975"), coord("37.3855"), coord("10")));
+        assert_ne!(first_zone.unwrap().zone_id, second_zone.unwrap().zone_id);
+    });
+}
+
+#[test]
+fn it_suggests_nonconflicting_box() {
+    new_test_ext().execute_with(|| {
+        setup_testing_zone();
+        let zone_box = DSMapsModule::zone_box(&DSMapsModule::zone_at(
+            Point3D::new(coord("55.3955"), coord("37.3855"), coord("10"))).unwrap());
+        let proposed = Box3D::new(Point3D::new(coord("55.3955"), coord("37.3855"), coord("0")),
+                                  Point3D::new(coord("55.3965"), coord("37.3865"), coord("10")));
+        assert!(DSMapsModule::boxes_intersect(zone_box, proposed));
+
+        let suggested = DSMapsModule::suggest_nonconflicting_box(proposed).unwrap();
+        assert!(!DSMapsModule::boxes_intersect(zone_box, suggested));
+        assert_eq!(suggested.north_east.lat - suggested.south_west.lat, proposed.north_east.lat - proposed.south_west.lat);
+        assert_eq!(suggested.north_east.lon - suggested.south_west.lon, proposed.north_east.lon - proposed.south_west.lon);
+
+        let above = Box3D::new(Point3D::new(coord("55.3955"), coord("37.3855"), coord("40")),
+                               Point3D::new(coord("55.3965"), coord("37.3865"), coord("50")));
+        assert_eq!(DSMapsModule::suggest_nonconflicting_box(above), Some(above));
+    });
+}
+
+#[test]
+fn it_rejects_inverted_zone() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        assert_noop!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_rect("55.396", "37.385", "55.395", "37.386"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            ),
+            Error::InvalidData
+        );
+        assert_noop!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                0,
+                ROOT_ID,
+            ),
+            Error::InvalidData
+        );
+        assert!(DSMapsModule::is_valid_box(&construct_testing_box()));
+    });
+}
+
+#[test]
+fn it_gets_zones_in_region() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        let second_rect = construct_custom_rect("55.397", "37.385", "55.398", "37.386");
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(construct_testing_rect(), DEFAULT_HEIGHT), (second_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+        ));
+        let first_only = Box3D::new(Point3D::new(coord("55.394"), coord("37.384"), coord("0")),
+                                    Point3D::new(coord("55.3955"), coord("37.3855"), coord("10")));
+        let zones = DSMapsModule::zones_in_region(first_only, 0, 10).unwrap();
+        assert_eq!(zones.len(), 1);
+        assert_eq!(zones[0].rect, construct_testing_rect());
+
+        let both = Box3D::new(Point3D::new(coord("55.394"), coord("37.384"), coord("0")),
+                              Point3D::new(coord("55.399"), coord("37.387"), coord("10")));
+        assert_eq!(DSMapsModule::zones_in_region(both, 0, 10).unwrap().len(), 2);
+        assert_eq!(DSMapsModule::zones_in_region(both, 1, 10).unwrap().len(), 1);
+        assert_eq!(DSMapsModule::zones_in_region(both, 0, 1).unwrap().len(), 1);
+
+        let above = Box3D::new(Point3D::new(coord("55.394"), coord("37.384"), coord("40")),
+                               Point3D::new(coord("55.399"), coord("37.387"), coord("50")));
+        assert!(DSMapsModule::zones_in_region(above, 0, 10).unwrap().is_empty());
+        // Region far from the root has no zones, and region too large isn't scanned
+        let far = Box3D::new(Point3D::new(coord("10"), coord("10"), coord("0")),
+                             Point3D::new(coord("10.05"), coord("10.05"), coord("10")));
+        assert_eq!(DSMapsModule::zones_in_region(far, 0, 10), Some(Vec::new()));
+        assert_eq!(DSMapsModule::zones_in_region(construct_testing_box(), 0, 10), None);
+    });
+}
+
+#[test]
+fn it_allows_only_author_to_remove_zone() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+        ));
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+        ));
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+        ));
+        let second_rect = construct_custom_rect("55.397", "37.385", "55.398", "37.386");
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(construct_testing_rect(), DEFAULT_HEIGHT), (second_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+        ));
+        let zones = DSMapsModule::zones_in_area(ROOT_ID, AREA_ID);
+        assert_eq!(DSMapsModule::author_of(zones[0]), Some(REGISTRAR_1_ACCOUNT_ID));
+
+        assert_noop!(
+            DSMapsModule::zone_remove(Origin::signed(REGISTRAR_2_ACCOUNT_ID), zones[0]),
+            Error::NotAuthorized
+        );
+        assert_ok!(DSMapsModule::zone_remove(Origin::signed(REGISTRAR_1_ACCOUNT_ID), zones[0]));
+        assert_eq!(DSMapsModule::author_of(zones[0]), None);
+        assert_ok!(DSMapsModule::zone_remove(Origin::root(), zones[1]));
+        assert_eq!(DSMapsModule::total_zones(), 0);
+    });
+}
+
+#[test]
+fn it_predicts_next_allocatable_id() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        let first_slot = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0);
+        assert_eq!(DSMapsModule::next_allocatable_id(ROOT_ID, AREA_ID), Some(first_slot));
+        let second_rect = construct_custom_rect("55.397", "37.385", "55.398", "37.386");
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(construct_testing_rect(), DEFAULT_HEIGHT), (second_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+        ));
+        assert_eq!(DSMapsModule::next_allocatable_id(ROOT_ID, AREA_ID), None);
+
+        assert_ok!(DSMapsModule::zone_remove(Origin::signed(REGISTRAR_1_ACCOUNT_ID), first_slot));
+        assert_eq!(DSMapsModule::next_allocatable_id(ROOT_ID, AREA_ID), Some(first_slot));
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        assert!(super::RedZones::<Test>::contains_key(first_slot));
+    });
+}
+
+#[test]
+fn it_soft_deletes_and_restores_zone() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_1_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+        ));
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+        ));
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+        ));
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let zone_id = DSMapsModule::zones_in_area(ROOT_ID, AREA_ID)[0];
+        let inside_zone = Point3D::new(coord("55.3955"), coord("37.3855"), coord("10"));
+        assert_noop!(
+            DSMapsModule::zone_soft_delete(Origin::signed(REGISTRAR_2_ACCOUNT_ID), zone_id),
+            Error::NotAuthorized
+        );
+        assert_noop!(
+            DSMapsModule::zone_restore(Origin::signed(REGISTRAR_1_ACCOUNT_ID), zone_id),
+            Error::InvalidAction
+        );
+
+        System::set_block_number(1);
+        assert_ok!(DSMapsModule::zone_soft_delete(Origin::signed(REGISTRAR_1_ACCOUNT_ID), zone_id));
+        assert_eq!(last_event(), Event::pallet_ds_maps(RawEvent::ZoneSoftDeleted(zone_id, REGISTRAR_1_ACCOUNT_ID)));
+        assert!(!DSMapsModule::is_restricted(inside_zone));
+        assert!(DSMapsModule::zone_at(inside_zone).is_none());
+        // Spatial queries skip it as well
+        let below_zone = Point3D::new(coord("55.3945"), coord("37.3855"), coord("10"));
+        assert!(DSMapsModule::reachable_zones(below_zone, Point2D::new(coord("1"), coord("0")), coord("0.002")).is_empty());
+        let over_zone = Box3D::new(Point3D::new(coord("55.3955"), coord("37.3855"), coord("0")),
+                                   Point3D::new(coord("55.3958"), coord("37.3858"), coord("10")));
+        assert_eq!(DSMapsModule::suggest_nonconflicting_box(over_zone), Some(over_zone));
+        assert_eq!(DSMapsModule::densest_region(), None);
+        // Record is kept
+        assert!(super::RedZones::<Test>::contains_key(zone_id));
+
+        assert_ok!(DSMapsModule::zone_restore(Origin::signed(ADMIN_ACCOUNT_ID), zone_id));
+        assert_eq!(last_event(), Event::pallet_ds_maps(RawEvent::ZoneRestored(zone_id, ADMIN_ACCOUNT_ID)));
+        assert!(DSMapsModule::is_restricted(inside_zone));
+        assert!(DSMapsModule::zone_at(inside_zone).is_some());
+    });
+}
+
+#[test]
+fn it_finds_densest_region() {
+    new_test_ext().execute_with(|| {
+        MaxBuildingsInArea::set(4);
+        setup_testing_root();
+        assert_eq!(DSMapsModule::densest_region(), None);
+        let zones = vec![
+            (construct_custom_rect("55.392", "37.382", "55.393", "37.383"), DEFAULT_HEIGHT),
+            (construct_custom_rect("55.393", "37.382", "55.394", "37.383"), DEFAULT_HEIGHT),
+            (construct_custom_rect("55.392", "37.383", "55.393", "37.384"), DEFAULT_HEIGHT),
+            // Another area
+            (construct_custom_rect("55.411", "37.372", "55.416", "37.375"), DEFAULT_HEIGHT),
+        ];
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                zones,
+                ROOT_ID,
+        ));
+        assert_eq!(DSMapsModule::densest_region(), Some((ROOT_ID, AREA_ID, 3)));
+        // Density follows removals
+        assert_ok!(DSMapsModule::zone_remove(Origin::root(), DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0)));
+        assert_ok!(DSMapsModule::zone_remove(Origin::root(), DSMapsModule::pack_index(ROOT_ID, AREA_ID, 1)));
+        assert_eq!(DSMapsModule::densest_region().map(|(_, _, count)| count), Some(1));
+        assert_eq!(DSMapsModule::area_density(ROOT_ID, AREA_ID), 1);
+    });
+}
+
+#[test]
+fn it_allows_root_origin_to_add_zones() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        assert_noop!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGULATOR_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            ),
+            Error::NotAuthorized
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::root(),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let second_rect = construct_custom_rect("55.397", "37.385", "55.398", "37.386");
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                second_rect,
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let zones = DSMapsModule::zones_in_area(ROOT_ID, AREA_ID);
+        assert_eq!(zones.len(), 2);
+        // Root is neither an author, nor rate limited
+        assert_eq!(DSMapsModule::author_of(zones[0]), None);
+        assert_eq!(DSMapsModule::author_of(zones[1]), Some(REGISTRAR_1_ACCOUNT_ID));
+        assert_eq!(DSAccountsModule::daily_operations(0).1, 0);
+
+        System::set_block_number(1);
+        assert_ok!(DSMapsModule::zone_remove(Origin::root(), zones[0]));
+        assert_eq!(last_event(), Event::pallet_ds_maps(RawEvent::ZoneRemoved(zones[0], None)));
+        assert_eq!(DSMapsModule::archived_zone(zones[0], 1).map(|(_, who)| who), Some(None));
+        assert_eq!(DSAccountsModule::daily_operations(0).1, 0);
+    });
+}
+
+#[test]
+fn it_computes_owner_covered_volume() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+        ));
+        let second_rect = construct_custom_rect("55.397", "37.385", "55.398", "37.386");
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(construct_testing_rect(), DEFAULT_HEIGHT), (second_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+        ));
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_2_ACCOUNT_ID),
+                construct_custom_rect("55.411", "37.372", "55.416", "37.375"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let first = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0);
+        let second = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 1);
+        // Zone overlaps itself with its whole volume
+        let first_volume = DSMapsModule::overlap_volume(first, first).unwrap();
+        let second_volume = DSMapsModule::overlap_volume(second, second).unwrap();
+        assert!(first_volume > 0);
+
+        assert_eq!(DSMapsModule::owner_covered_volume(&REGISTRAR_1_ACCOUNT_ID), Some(first_volume + second_volume));
+        assert_eq!(DSMapsModule::owner_covered_volume(&ADMIN_ACCOUNT_ID), Some(0));
+
+        assert_ok!(DSMapsModule::zone_remove(Origin::root(), second));
+        assert_eq!(DSMapsModule::owner_covered_volume(&REGISTRAR_1_ACCOUNT_ID), Some(first_volume));
+    });
+}
+
+#[test]
+fn it_computes_owner_distribution() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        assert_ok!(
+            DSAccountsModule::account_add(
+                Origin::signed(ADMIN_ACCOUNT_ID),
+                REGISTRAR_2_ACCOUNT_ID,
+                super::REGISTRAR_ROLE
+        ));
+        let second_rect = construct_custom_rect("55.397", "37.385", "55.398", "37.386");
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(construct_testing_rect(), DEFAULT_HEIGHT), (second_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+        ));
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_2_ACCOUNT_ID),
+                construct_custom_rect("55.411", "37.372", "55.416", "37.375"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        // Zone of root origin has no owner
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::root(),
+                construct_custom_rect("55.421", "37.372", "55.426", "37.375"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let mut distribution = DSMapsModule::owner_distribution(0, 10);
+        distribution.sort();
+        assert_eq!(distribution, vec![(REGISTRAR_1_ACCOUNT_ID, 2), (REGISTRAR_2_ACCOUNT_ID, 1)]);
+
+        // Pages don't repeat owners
+        let first_page = DSMapsModule::owner_distribution(0, 1);
+        let second_page = DSMapsModule::owner_distribution(1, 1);
+        assert_eq!(first_page.len(), 1);
+        assert_eq!(second_page.len(), 1);
+        assert_ne!(first_page[0].0, second_page[0].0);
+        assert!(DSMapsModule::owner_distribution(2, 10).is_empty());
+
+        // Soft deleted zones are not counted
+        let second = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 1);
+        assert_ok!(DSMapsModule::zone_soft_delete(Origin::signed(REGISTRAR_1_ACCOUNT_ID), second));
+        let mut distribution = DSMapsModule::owner_distribution(0, 10);
+        distribution.sort();
+        assert_eq!(distribution, vec![(REGISTRAR_1_ACCOUNT_ID, 1), (REGISTRAR_2_ACCOUNT_ID, 1)]);
+    });
+}
+
+#[test]
+fn it_lists_zones_of_disabled_owners() {
+    new_test_ext().execute_with(|| {
+        let zone_id = setup_testing_zone();
+        // Zone of root origin has no owner to be disabled
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::root(),
+                construct_custom_rect("55.411", "37.372", "55.416", "37.375"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        assert!(DSMapsModule::orphaned_zones(0, 10).is_empty());
+
+        assert_ok!(DSAccountsModule::account_disable(Origin::signed(ADMIN_ACCOUNT_ID), REGISTRAR_1_ACCOUNT_ID));
+        assert_eq!(DSMapsModule::orphaned_zones(0, 10), vec![zone_id]);
+        assert!(DSMapsModule::orphaned_zones(1, 10).is_empty());
+        assert!(DSMapsModule::orphaned_zones(0, 0).is_empty());
+    });
+}
+
+#[test]
+fn it_adds_named_zone() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        assert_noop!(
+            DSMapsModule::zone_add_named(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+                vec![b'x'; 17],
+            ),
+            Error::InvalidData
+        );
+        assert_ok!(
+            DSMapsModule::zone_add_named(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+                b"helipad".to_vec(),
+        ));
+        let zone_id = DSMapsModule::zones_in_area(ROOT_ID, AREA_ID)[0];
+        assert_eq!(DSMapsModule::zone_data(zone_id).name, b"helipad".to_vec());
+    });
+}
+
+#[test]
+fn it_returns_created_zone_id() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        let predicted = DSMapsModule::next_allocatable_id(ROOT_ID, AREA_ID);
+        let zone_id = DSMapsModule::create_zone(
+            Some(REGISTRAR_1_ACCOUNT_ID),
+            construct_testing_rect(),
+            DEFAULT_HEIGHT,
+            ROOT_ID,
+            Vec::new(),
+        ).unwrap();
+        assert_eq!(Some(zone_id), predicted);
+        assert_eq!(DSMapsModule::author_of(zone_id), Some(REGISTRAR_1_ACCOUNT_ID));
+    });
+}
+
+#[test]
+fn it_blocks_zone_creation_during_region_cooldown() {
+    new_test_ext().execute_with(|| {
+        RegionCooldownBlocks::set(5);
+        MaxBuildingsInArea::set(4);
+        System::set_block_number(1);
+        setup_testing_zone();
+        let second_rect = construct_custom_rect("55.397", "37.385", "55.398", "37.386");
+        System::set_block_number(5);
+        assert_noop!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                second_rect,
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+            ),
+            Error::RegionCoolingDown
+        );
+        // Other areas are not affected
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_rect("55.411", "37.372", "55.416", "37.375"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        System::set_block_number(6);
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                second_rect,
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        // Batch can't add into a cooling area, but its own zones don't block each other
+        let third_rect = construct_custom_rect("55.392", "37.382", "55.393", "37.383");
+        let fourth_rect = construct_custom_rect("55.393", "37.384", "55.394", "37.385");
+        assert_noop!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(third_rect, DEFAULT_HEIGHT), (fourth_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+            ),
+            Error::RegionCoolingDown
+        );
+        System::set_block_number(11);
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(third_rect, DEFAULT_HEIGHT), (fourth_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+        ));
+        assert_eq!(DSMapsModule::zone_data(DSMapsModule::pack_index(ROOT_ID, AREA_ID, 3)).height, DEFAULT_HEIGHT);
+    });
+}
+
+#[test]
+fn it_finds_boundary_zones() {
+    new_test_ext().execute_with(|| {
+        setup_testing_zone();
+        assert!(DSMapsModule::boundary_zones(ROOT_ID).is_empty());
+        // Flush against the south-western edge of the root
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_rect("55.371", "37.375", "55.372", "37.376"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let boundary = DSMapsModule::boundary_zones(ROOT_ID);
+        assert_eq!(boundary.len(), 1);
+        assert_eq!(DSMapsModule::zone_data(boundary[0]).rect.south_west.lat, coord("55.371"));
+        assert!(DSMapsModule::boundary_zones(ROOT_ID + 1).is_empty());
+    });
+}
+
+#[test]
+fn it_computes_total_red_volume() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(DSMapsModule::total_red_volume(), 0);
+        setup_testing_root();
+        let second_rect = construct_custom_rect("55.397", "37.385", "55.398", "37.386");
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(construct_testing_rect(), DEFAULT_HEIGHT), (second_rect, 2 * DEFAULT_HEIGHT)],
+                ROOT_ID,
+        ));
+        let first = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0);
+        let second = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 1);
+        let first_volume = DSMapsModule::overlap_volume(first, first).unwrap();
+        let second_volume = DSMapsModule::overlap_volume(second, second).unwrap();
+        assert_eq!(DSMapsModule::total_red_volume(), first_volume + second_volume);
+
+        assert_ok!(DSMapsModule::zone_set_frozen(Origin::signed(REGISTRAR_1_ACCOUNT_ID), second, true));
+        assert_eq!(DSMapsModule::total_red_volume(), first_volume);
+    });
+}
+
+#[test]
+fn it_records_zone_creation_block() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        System::set_block_number(7);
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let zone_id = DSMapsModule::zones_in_area(ROOT_ID, AREA_ID)[0];
+        assert_eq!(DSMapsModule::created_at(zone_id), Some(7));
+
+        assert_ok!(DSMapsModule::zone_remove(Origin::signed(REGISTRAR_1_ACCOUNT_ID), zone_id));
+        assert_eq!(DSMapsModule::created_at(zone_id), None);
+    });
+}
+
+#[test]
+fn it_finds_zones_created_between_blocks() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        System::set_block_number(50);
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let zone_id = DSMapsModule::zones_in_area(ROOT_ID, AREA_ID)[0];
+        assert_eq!(DSMapsModule::zones_created_between(40, 60), vec![zone_id]);
+        assert_eq!(DSMapsModule::zones_created_between(50, 50), vec![zone_id]);
+        assert!(DSMapsModule::zones_created_between(60, 80).is_empty());
+        assert!(DSMapsModule::zones_created_between(60, 40).is_empty());
+        assert!(DSMapsModule::zones_created_between(u64::MAX, u64::MAX).is_empty());
+
+        assert_ok!(DSMapsModule::zone_remove(Origin::signed(REGISTRAR_1_ACCOUNT_ID), zone_id));
+        assert!(DSMapsModule::zones_created_between(40, 60).is_empty());
+    });
+}
+
+#[test]
+fn it_computes_region_stats() {
+    new_test_ext().execute_with(|| {
+        for registrar in [REGISTRAR_1_ACCOUNT_ID, REGISTRAR_2_ACCOUNT_ID].iter() {
+            assert_ok!(
+                DSAccountsModule::account_add(
+                    Origin::signed(ADMIN_ACCOUNT_ID),
+                    *registrar,
+                    super::REGISTRAR_ROLE
+            ));
+        }
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_box(),
+                coord(DELTA),
+        ));
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![
+                    (construct_testing_rect(), DEFAULT_HEIGHT),
+                    (construct_custom_rect("55.397", "37.385", "55.398", "37.386"), DEFAULT_HEIGHT),
+                ],
+                ROOT_ID,
+        ));
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_2_ACCOUNT_ID),
+                construct_custom_rect("55.411", "37.372", "55.416", "37.375"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let frozen_zone = DSMapsModule::zones_in_area(ROOT_ID, AREA_ID)[0];
+        assert_ok!(DSMapsModule::zone_set_frozen(Origin::signed(REGISTRAR_1_ACCOUNT_ID), frozen_zone, true));
+
+        let stats = DSMapsModule::region_stats(construct_custom_box("55.39", "37.37", "55.42", "37.39")).unwrap();
+        assert_eq!(stats.enforced_zones, 2);
+        assert_eq!(stats.frozen_zones, 1);
+        assert_eq!(stats.owners, 2);
+        assert!(stats.covered_volume > 0);
+
+        // Region far from the root contains nothing
+        let empty = DSMapsModule::region_stats(construct_custom_box("10", "10", "10.05", "10.05"));
+        assert_eq!(empty, Some(Default::default()));
+        // Region too large isn't scanned
+        assert_eq!(DSMapsModule::region_stats(construct_testing_box()), None);
+    });
+}
+
+#[test]
+fn it_removes_expired_zones() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        setup_testing_root();
+        assert_noop!(
+            DSMapsModule::zone_add_temporary(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+                1,
+            ),
+            Error::WrongTimeSupplied
+        );
+        assert_ok!(
+            DSMapsModule::zone_add_temporary(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+                5,
+        ));
+        let zone_id = DSMapsModule::zones_in_area(ROOT_ID, AREA_ID)[0];
+        assert_eq!(DSMapsModule::expires_at(zone_id), Some(5));
+
+        System::set_block_number(4);
+        DSMapsModule::on_initialize(4);
+        assert!(super::RedZones::<Test>::contains_key(zone_id));
+
+        System::set_block_number(5);
+        DSMapsModule::on_initialize(5);
+        assert!(!super::RedZones::<Test>::contains_key(zone_id));
+        assert_eq!(DSMapsModule::expires_at(zone_id), None);
+        assert_eq!(DSMapsModule::total_zones(), 0);
+        assert_eq!(DSMapsModule::archived_zone(zone_id, 5).map(|(_, who)| who), Some(None));
+        assert_eq!(last_event(), Event::pallet_ds_maps(RawEvent::ZoneExpired(zone_id)));
+    });
+}
+
+#[test]
+fn it_limits_expiries_per_block() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        setup_testing_root();
+        let rects = [
+            construct_testing_rect(),
+            construct_custom_rect("55.397", "37.385", "55.398", "37.386"),
+            construct_custom_rect("55.411", "37.372", "55.416", "37.375"),
+        ];
+        for rect in rects.iter().take(MaxExpiriesPerBlock::get() as usize) {
+            assert_ok!(
+                DSMapsModule::zone_add_temporary(
+                    Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                    *rect,
+                    DEFAULT_HEIGHT,
+                    ROOT_ID,
+                    5,
+            ));
+        }
+        assert_noop!(
+            DSMapsModule::zone_add_temporary(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                rects[2],
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+                5,
+            ),
+            Error::ExpiryQueueFull
+        );
+        assert_ok!(
+            DSMapsModule::zone_add_temporary(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                rects[2],
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+                6,
+        ));
+    });
+}
+
+#[test]
+fn it_gets_zones_in_altitude_band() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![
+                    (construct_testing_rect(), 10),
+                    (construct_custom_rect("55.397", "37.385", "55.398", "37.386"), 50),
+                ],
+                ROOT_ID,
+        ));
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_rect("55.411", "37.372", "55.416", "37.375"),
+                100,
+                ROOT_ID,
+        ));
+        let middle = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 1);
+        let highest = DSMapsModule::zone_at(Point3D::new(coord("55.412"), coord("37.373"), coord("1"))).unwrap().zone_id;
+
+        let mut zones = DSMapsModule::zones_in_altitude_band(ROOT_ID, coord("40"), coord("60"));
+        zones.sort();
+        let mut expected = vec![middle, highest];
+        expected.sort();
+        assert_eq!(zones, expected);
+        assert_eq!(DSMapsModule::zones_in_altitude_band(ROOT_ID, coord("60"), coord("90")), vec![highest]);
+        // Band, touching top of the highest zone
+        assert!(DSMapsModule::zones_in_altitude_band(ROOT_ID, coord("100"), coord("200")).is_empty());
+        assert!(DSMapsModule::zones_in_altitude_band(ROOT_ID + 1, coord("40"), coord("60")).is_empty());
+    });
+}
+
+#[test]
+fn it_checks_segment_against_zones() {
+    new_test_ext().execute_with(|| {
+        let zone_id = setup_testing_zone();
+        let point = |lat: &str, lon: &str, alt: &str| Point3D::<Coord>::new(coord(lat), coord(lon), coord(alt));
+
+        // Clips south-eastern corner of the zone
+        assert_eq!(
+            DSMapsModule::check_segment(point("55.3948", "37.3855", "10"), point("55.3958", "37.3845", "10")),
+            Some(vec![zone_id])
+        );
+        // Lies inside the zone
+        assert_eq!(
+            DSMapsModule::check_segment(point("55.3952", "37.3852", "5"), point("55.3958", "37.3858", "20")),
+            Some(vec![zone_id])
+        );
+        // Passes above the zone
+        assert_eq!(DSMapsModule::check_segment(point("55.3945", "37.3855", "40"), point("55.3965", "37.3855", "40")), Some(vec![]));
+        // Passes beside the zone
+        assert_eq!(DSMapsModule::check_segment(point("55.3945", "37.3865", "10"), point("55.3965", "37.3875", "10")), Some(vec![]));
+        // Ends outside of all roots, so it can't be reported as clear
+        assert_eq!(DSMapsModule::check_segment(point("10", "10", "10"), point("55.3955", "37.3855", "10")), None);
+        assert_eq!(DSMapsModule::check_segment(point("55.3955", "37.3855", "10"), point("10", "10", "10")), None);
+    });
+}
+
+#[test]
+fn it_checks_segment_across_roots() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        // Small root just north of the testing one
+        assert_ok!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_box("55.921", "37.371", "55.941", "37.401"),
+                coord(DELTA),
+        ));
+        let north_root = super::RootBoxes::<Test>::iter()
+            .map(|(root_id, _)| root_id)
+            .find(|root_id| *root_id != ROOT_ID)
+            .unwrap();
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_rect("55.925", "37.375", "55.926", "37.376"),
+                DEFAULT_HEIGHT,
+                north_root,
+        ));
+        let zone_id = super::RedZones::<Test>::iter().map(|(zone_id, _)| zone_id).next().unwrap();
+        let point = |lat: &str, lon: &str, alt: &str| Point3D::<Coord>::new(coord(lat), coord(lon), coord(alt));
+
+        // Starts in the testing root, ends inside the zone of the northern one
+        assert_eq!(
+            DSMapsModule::check_segment(point("55.9", "37.3755", "10"), point("55.9255", "37.3755", "10")),
+            Some(vec![zone_id])
+        );
+    });
+}
+
+#[test]
+fn it_migrates_zones_to_named_layout() {
+    use crate::{migrations::{self, ZoneV1}, Releases};
+
+    new_test_ext().execute_with(|| {
+        let zone_id = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0);
+        let old_zone = ZoneV1 {zone_id, rect: construct_testing_rect(), height: DEFAULT_HEIGHT};
+        unhashed::put(&super::RedZones::<Test>::hashed_key_for(zone_id), &old_zone);
+        assert_eq!(DSMapsModule::storage_version(), Releases::V1);
+
+        migrations::migrate_to_v2::<Test>();
+        assert_eq!(DSMapsModule::storage_version(), Releases::V2);
+        let zone = DSMapsModule::zone_data(zone_id);
+        assert_eq!(zone.rect, construct_testing_rect());
+        assert_eq!(zone.height, DEFAULT_HEIGHT);
+        assert!(zone.name.is_empty());
+        assert_eq!(DSMapsModule::densest_region(), Some((ROOT_ID, AREA_ID, 1)));
+        assert_eq!(DSMapsModule::total_zones(), 1);
+
+        // Zones in the latest layout are left intact
+        super::RedZones::<Test>::insert(2, Zone::new(2, construct_testing_rect(), 1).with_name(b"helipad".to_vec()));
+        migrations::migrate_to_v2::<Test>();
+        assert_eq!(DSMapsModule::zone_data(2).name, b"helipad".to_vec());
+    });
+}
+
+#[test]
+fn it_rejects_zones_outside_map_bounds() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        setup_testing_root();
+        let bounds = Box3D::new(
+            Point3D::new(coord("55"), coord("37"), coord("0")),
+            Point3D::new(coord("56"), coord("38"), coord("100")),
+        );
+        assert_noop!(DSMapsModule::map_init(Origin::signed(ADMIN_ACCOUNT_ID), bounds), DispatchError::BadOrigin);
+        // Bounds should cover existing roots
+        let narrow_bounds = Box3D::new(
+            Point3D::new(coord("55"), coord("37"), coord("0")),
+            Point3D::new(coord("55.5"), coord("38"), coord("100")),
+        );
+        assert_noop!(DSMapsModule::map_init(Origin::root(), narrow_bounds), Error::OutsideMapBounds);
+        assert_ok!(DSMapsModule::map_init(Origin::root(), bounds));
+        assert_eq!(last_event(), Event::pallet_ds_maps(RawEvent::MapInitialized(bounds)));
+
+        // Roots outside of bounds are rejected
+        assert_noop!(
+            DSMapsModule::root_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_box("56.1", "37.1", "56.2", "37.2"),
+                coord(DELTA),
+            ),
+            Error::OutsideMapBounds
+        );
+        // Root can correct bounds later
+        let wider_bounds = Box3D::new(
+            Point3D::new(coord("54"), coord("36"), coord("0")),
+            Point3D::new(coord("57"), coord("39"), coord("100")),
+        );
+        assert_ok!(DSMapsModule::map_init(Origin::root(), wider_bounds));
+        assert_eq!(DSMapsModule::map_bounds(), Some(wider_bounds));
+        assert_ok!(DSMapsModule::map_init(Origin::root(), bounds));
+
+        assert_noop!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                200,
+                ROOT_ID,
+            ),
+            Error::OutsideMapBounds
+        );
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_testing_rect(),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+    });
+}
+
+#[test]
+fn it_checks_zones_conflict() {
+    new_test_ext().execute_with(|| {
+        setup_testing_root();
+        let touching_rect = construct_custom_rect("55.396", "37.385", "55.397", "37.386");
+        assert_ok!(
+            DSMapsModule::zone_add_batch(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                vec![(construct_testing_rect(), DEFAULT_HEIGHT), (touching_rect, DEFAULT_HEIGHT)],
+                ROOT_ID,
+        ));
+        assert_ok!(
+            DSMapsModule::zone_add(
+                Origin::signed(REGISTRAR_1_ACCOUNT_ID),
+                construct_custom_rect("55.411", "37.372", "55.416", "37.375"),
+                DEFAULT_HEIGHT,
+                ROOT_ID,
+        ));
+        let zone = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 0);
+        let touching_zone = DSMapsModule::pack_index(ROOT_ID, AREA_ID, 1);
+        let other_area_zone = DSMapsModule::zone_at(Point3D::new(coord("55.412"), coord("37.373"), coord("1"))).unwrap().zone_id;
+
+        // zone_add keeps zones apart, so a zone conflicts only with itself
+        assert_eq!(DSMapsModule::zones_conflict(zone, zone).ok(), Some(true));
+        // Shares a face only
+        assert_eq!(DSMapsModule::zones_conflict(zone, touching_zone).ok(), Some(false));
+        assert_eq!(DSMapsModule::zones_conflict(zone, other_area_zone).ok(), Some(false));
+        assert_err!(
+            DSMapsModule::zones_conflict(zone, DSMapsModule::pack_index(ROOT_ID, AREA_ID, 2)).map_err(DispatchError::from),
+            Error::ZoneDoesntExist
+        );
+    });
+}
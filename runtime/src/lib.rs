@@ -51,6 +51,8 @@ pub use pallet_ds_accounts;
 pub use pallet_ds_maps;
 /// Import fixed point for GPS coords
 use substrate_fixed::types::{I10F22, I42F22};
+/// GPS coordinate, used by the DS maps pallet
+pub type Coord = I10F22;
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -105,7 +107,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("node-dsky"),
     impl_name: create_runtime_str!("node-dsky"),
     authoring_version: 1,
-    spec_version: 1,
+    spec_version: 2,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
     transaction_version: 1,
@@ -208,7 +210,7 @@ impl frame_system::Config for Runtime {
     /// The data to be stored in an account.
     type AccountData = pallet_balances::AccountData<Balance>;
     /// What to do if a new account is created.
-    type OnNewAccount = ();
+    type OnNewAccount = DSAccountsModule;
     /// What to do if an account is fully reaped from the system.
     type OnKilledAccount = DSAccountsModule;
     /// Weight information for the extrinsics of this pallet.
@@ -288,6 +290,9 @@ impl pallet_sudo::Config for Runtime {
 // Configure pallet constants
 parameter_types! {
     pub const AdminRole: u8 = ADMIN_ROLE;
+    pub const AutoReenableOnTopUp: bool = false;
+    pub const MaxOpsPerDay: u32 = 1_000;
+    pub const BlocksPerDay: BlockNumber = DAYS;
     pub const RegistrarRole: u8 = REGISTRAR_ROLE;
 }
 
@@ -300,12 +305,30 @@ impl pallet_ds_accounts::Trait for Runtime {
     type WeightInfo = ();
     type MetaIPFS = Vec<u8>;
     type SerialNumber = Vec<u8>;    //guess, this should be UTF-8 encoded
+    type AutoReenableOnTopUp = AutoReenableOnTopUp;
+    type MaxOpsPerDay = MaxOpsPerDay;
+    type BlocksPerDay = BlocksPerDay;
 }
 
 // After researches, consider placing here max grid sizes
 parameter_types! {
     pub const MaxHeight: u32 = 400;
     pub const MaxBuildingsInArea: u16 = 100;
+    pub const ZoneEventVerbosity: pallet_ds_maps::EventVerbosity = pallet_ds_maps::EventVerbosity::Standard;
+    /// ds-maps migrates every zone within the upgrade block, up to a read and three writes each,
+    /// so the cap keeps such migration below MAXIMUM_BLOCK_WEIGHT
+    pub const MaxTotalZones: u32 = 5_000;
+    /// Kept off, so the cap above also bounds the migration
+    pub const AdminsExemptFromMaxTotalZones: bool = false;
+    pub const MaxAttestationLength: u32 = 256;
+    pub const MaxZoneNameLen: u32 = 64;
+    pub const OutOfWorld: pallet_ds_maps::OutOfWorldPolicy = pallet_ds_maps::OutOfWorldPolicy::Restricted;
+    pub const ArchiveRemovedZones: bool = true;
+    pub const ArchiveRetention: BlockNumber = 30 * DAYS;
+    pub const RestrictedAltitudeBuffer: u32 = 10;
+    pub const MaxZonesPerBatch: u32 = 100;
+    pub const RegionCooldownBlocks: BlockNumber = MINUTES;
+    pub const MaxExpiriesPerBlock: u32 = 64;
 }
 
 /// Configure the DS maps pallet in pallets/ds-maps.
@@ -313,10 +336,22 @@ impl pallet_ds_maps::Trait for Runtime {
     type Event = Event;
     type WeightInfo = ();
     type RawCoord = i32;
-    type Coord = I10F22;
+    type Coord = Coord;
     type BigCoord = I42F22;
     type MaxBuildingsInArea = MaxBuildingsInArea;
     type MaxHeight = MaxHeight;
+    type EventVerbosity = ZoneEventVerbosity;
+    type MaxTotalZones = MaxTotalZones;
+    type AdminsExemptFromMaxTotalZones = AdminsExemptFromMaxTotalZones;
+    type MaxAttestationLength = MaxAttestationLength;
+    type MaxZoneNameLen = MaxZoneNameLen;
+    type OutOfWorldPolicy = OutOfWorld;
+    type ArchiveRemovedZones = ArchiveRemovedZones;
+    type ArchiveRetention = ArchiveRetention;
+    type RestrictedAltitudeBuffer = RestrictedAltitudeBuffer;
+    type MaxZonesPerBatch = MaxZonesPerBatch;
+    type RegionCooldownBlocks = RegionCooldownBlocks;
+    type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -336,7 +371,7 @@ construct_runtime!(
         Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
         // Include the DS account management logic from ds-accounts pallet in the runtime
         DSAccountsModule: pallet_ds_accounts::{Module, Call, Storage, Config<T>, Event<T>},
-        DSMapsModule: pallet_ds_maps::{Module, Call, Storage, Event<T>},
+        DSMapsModule: pallet_ds_maps::{Module, Call, Config, Storage, Event<T>},
     }
 );
 
@@ -503,6 +538,27 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_ds_maps_runtime_api::DSMapsApi<Block, Coord> for Runtime {
+        fn zones_in_region(
+            region: pallet_ds_maps::Box3D<Coord>,
+            offset: u32,
+            limit: u32,
+        ) -> Option<Vec<pallet_ds_maps::Zone<Coord>>> {
+            DSMapsModule::zones_in_region(region, offset, limit)
+        }
+
+        fn check_segment(
+            from: pallet_ds_maps::Point3D<Coord>,
+            to: pallet_ds_maps::Point3D<Coord>,
+        ) -> Option<Vec<u128>> {
+            DSMapsModule::check_segment(from, to)
+        }
+
+        fn zones_conflict(a: u128, b: u128) -> Option<bool> {
+            DSMapsModule::zones_conflict(a, b).ok()
+        }
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     impl frame_benchmarking::Benchmark<Block> for Runtime {
         fn dispatch_benchmark(
@@ -532,6 +588,7 @@ impl_runtime_apis! {
             add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
             add_benchmark!(params, batches, pallet_balances, Balances);
             add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+            add_benchmark!(params, batches, pallet_ds_maps, DSMapsModule);
 
             if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
             Ok(batches)